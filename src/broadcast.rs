@@ -0,0 +1,298 @@
+use alloc::vec::Vec;
+
+use crate::{Identifier, VClock, VClockTime};
+
+/// Causal broadcast on top of a local vector clock
+///
+/// `broadcast` stamps outgoing payloads with the incremented local clock.
+/// `receive` hands incoming messages to a `CausalBuffer`, which releases them
+/// only once every message they causally depend on has been released.
+/// Duplicates of already delivered messages are dropped.
+#[derive(Debug, Clone)]
+pub struct CausalBroadcast<T> {
+    id: Identifier,
+    buffer: CausalBuffer<T>,
+}
+
+impl<T> CausalBroadcast<T> {
+    /// Creates a sequencer for the node `id` with an empty clock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{CausalBroadcast, Identifier};
+    ///
+    /// let (a, b) = (Identifier::new(), Identifier::new());
+    /// let mut sender = CausalBroadcast::new(a.clone());
+    /// let mut receiver = CausalBroadcast::new(b);
+    ///
+    /// let message = sender.broadcast("hello");
+    /// assert_eq!(receiver.receive(message, &a), vec!["hello"]);
+    /// ```
+    pub fn new(id: Identifier) -> Self {
+        CausalBroadcast {
+            id,
+            buffer: CausalBuffer::new(),
+        }
+    }
+
+    /// Records a local send event and returns the payload stamped with the
+    /// new clock, ready to be sent to every peer
+    pub fn broadcast(&mut self, payload: T) -> (T, VClockTime) {
+        self.buffer.delivered.increment(&self.id);
+        (payload, self.buffer.delivered.time())
+    }
+
+    /// Buffers a message received from `from` and returns, in causal order,
+    /// every payload that became deliverable as a result
+    ///
+    /// The message is only released once `VClock::can_deliver` accepts it from
+    /// `from`, so a message attributed to the wrong sender is never delivered.
+    pub fn receive(&mut self, message: (T, VClockTime), from: &Identifier) -> Vec<T> {
+        let (payload, time) = message;
+        self.buffer.enqueue(time, Some(from.clone()), payload);
+        self.buffer.deliverable()
+    }
+
+    /// Returns the number of received messages still waiting for their
+    /// causal dependencies
+    pub fn pending(&self) -> usize {
+        self.buffer.pending()
+    }
+
+    /// Returns the local clock, covering local broadcasts and delivered
+    /// messages
+    pub fn clock(&self) -> &VClock {
+        self.buffer.delivered()
+    }
+}
+
+/// A causal-delivery buffer: holds back received messages until every message
+/// they causally depend on has been delivered
+///
+/// Unlike `CausalBroadcast`, messages carry only their vector clock. A message
+/// stamped `V` is released once some node `j` has `V[j] == local[j] + 1` and
+/// `V[k] <= local[k]` for every other node `k`, where `local` is the clock of
+/// delivered messages; `j` is necessarily the sender. Messages whose clock is
+/// already covered by `local`, and copies of a message that is still waiting,
+/// are duplicates and are dropped.
+#[derive(Debug, Clone)]
+pub struct CausalBuffer<T> {
+    delivered: VClock,
+    buffer: Vec<(VClockTime, Option<Identifier>, T)>,
+}
+
+impl<T> CausalBuffer<T> {
+    /// Creates an empty buffer that has delivered nothing yet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{CausalBuffer, Identifier, VClock};
+    ///
+    /// let sender = Identifier::new();
+    /// let mut clock = VClock::new();
+    /// clock.increment(&sender);
+    /// let first = clock.time();
+    /// clock.increment(&sender);
+    /// let second = clock.time();
+    ///
+    /// let mut buffer = CausalBuffer::new();
+    /// buffer.push(second, "second");
+    /// assert!(buffer.deliverable().is_empty());
+    /// buffer.push(first, "first");
+    /// assert_eq!(buffer.deliverable(), vec!["first", "second"]);
+    /// ```
+    pub fn new() -> Self {
+        CausalBuffer::with_clock(VClock::new())
+    }
+
+    /// Creates an empty buffer that has already delivered everything covered
+    /// by `delivered`
+    pub fn with_clock(delivered: VClock) -> Self {
+        CausalBuffer {
+            delivered,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Buffers a received message, dropping it if it was already delivered or
+    /// an identical message is already waiting
+    pub fn push(&mut self, time: VClockTime, payload: T) {
+        self.enqueue(time, None, payload);
+    }
+
+    /// Removes and returns, in causal order, every buffered payload whose
+    /// dependencies have all been delivered
+    pub fn deliverable(&mut self) -> Vec<T> {
+        let mut delivered = Vec::new();
+        while let Some((i, sender)) = self.buffer.iter().enumerate().find_map(|(i, (time, from, _))| {
+            from.as_ref()
+                .or_else(|| self.sender_of(time))
+                .filter(|sender| self.delivered.can_deliver(time, sender))
+                .map(|sender| (i, sender.clone()))
+        }) {
+            let (time, _, payload) = self.buffer.remove(i);
+            self.delivered.vector.insert(sender.clone(), time.0[&sender]);
+            delivered.push(payload);
+        }
+
+        // Messages already covered by the delivered clock can never be released
+        let clock = &self.delivered;
+        self.buffer.retain(|(time, _, _)| !covered(clock, time));
+        delivered
+    }
+
+    /// Returns the number of messages still waiting for their causal
+    /// dependencies
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the clock of delivered messages
+    pub fn delivered(&self) -> &VClock {
+        &self.delivered
+    }
+
+    /// Buffers a message whose sender, if known, is checked on delivery instead
+    /// of being recovered from its clock
+    fn enqueue(&mut self, time: VClockTime, from: Option<Identifier>, payload: T) {
+        let waiting = self.buffer.iter().any(|(buffered, _, _)| *buffered == time);
+        if !waiting && !covered(&self.delivered, &time) {
+            self.buffer.push((time, from, payload));
+        }
+    }
+
+    /// Returns the only node that can have sent `time`: the first node whose
+    /// counter is ahead of the delivered clock
+    fn sender_of<'a>(&self, time: &'a VClockTime) -> Option<&'a Identifier> {
+        time.0
+            .iter()
+            .find(|(node, &counter)| !self.delivered.already_seen(node, counter))
+            .map(|(node, _)| node)
+    }
+}
+
+impl<T> Default for CausalBuffer<T> {
+    fn default() -> Self {
+        CausalBuffer::new()
+    }
+}
+
+/// Checks whether every entry of `time` has already been delivered into `clock`
+fn covered(clock: &VClock, time: &VClockTime) -> bool {
+    time.0.iter().all(|(node, &counter)| clock.already_seen(node, counter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_causal_broadcast_out_of_order_chain() {
+        let ids = Identifier::generate(3);
+        let mut a = CausalBroadcast::new(ids[0].clone());
+        let mut b = CausalBroadcast::new(ids[1].clone());
+        let mut c = CausalBroadcast::new(ids[2].clone());
+
+        // a sends two messages, b replies after seeing them
+        let a1 = a.broadcast("a1");
+        let a2 = a.broadcast("a2");
+        assert_eq!(b.receive(a1.clone(), &ids[0]), vec!["a1"]);
+        assert_eq!(b.receive(a2.clone(), &ids[0]), vec!["a2"]);
+        let b1 = b.broadcast("b1");
+
+        // c receives the chain in reverse
+        assert!(c.receive(b1, &ids[1]).is_empty());
+        assert!(c.receive(a2.clone(), &ids[0]).is_empty());
+        assert_eq!(c.pending(), 2);
+        assert_eq!(c.receive(a1.clone(), &ids[0]), vec!["a1", "a2", "b1"]);
+        assert_eq!(c.pending(), 0);
+
+        // Redelivered duplicates are dropped
+        assert!(c.receive(a2, &ids[0]).is_empty());
+        assert_eq!(c.pending(), 0);
+        assert_eq!(c.clock(), b.clock());
+
+        // A message attributed to the wrong sender is held back
+        let a3 = a.broadcast("a3");
+        assert!(c.receive(a3, &ids[1]).is_empty());
+        assert_eq!(c.pending(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_causal_buffer_releases_in_causal_order() {
+        let ids = Identifier::generate(3);
+        let mut a = VClock::new();
+        a.increment(&ids[0]);
+        let a1 = a.time();
+        a.increment(&ids[0]);
+        let a2 = a.time();
+
+        let mut b = a.clone();
+        b.increment(&ids[1]);
+        let b1 = b.time();
+
+        let mut c = VClock::new();
+        c.increment(&ids[2]);
+        let c1 = c.time();
+
+        let mut buffer = CausalBuffer::new();
+        buffer.push(b1, "b1");
+        buffer.push(a2.clone(), "a2");
+        assert!(buffer.deliverable().is_empty());
+        assert_eq!(buffer.pending(), 2);
+
+        // Concurrent messages are delivered as soon as they arrive
+        buffer.push(c1, "c1");
+        assert_eq!(buffer.deliverable(), vec!["c1"]);
+
+        buffer.push(a1, "a1");
+        assert_eq!(buffer.deliverable(), vec!["a1", "a2", "b1"]);
+        assert_eq!(buffer.pending(), 0);
+
+        // Duplicates of delivered messages are dropped
+        buffer.push(a2, "a2");
+        assert_eq!(buffer.pending(), 0);
+
+        let mut expected = b.clone();
+        expected.merge(&c);
+        assert_eq!(buffer.delivered(), &expected);
+    }
+
+    #[test]
+    fn test_causal_buffer_holds_messages_skipping_a_sender_event() {
+        let id = Identifier::from_bytes(vec![1]);
+        let mut clock = VClock::new();
+        clock.increment(&id);
+        let mut buffer = CausalBuffer::with_clock(clock.clone());
+
+        clock.increment(&id);
+        clock.increment(&id);
+        buffer.push(clock.time(), 3);
+        assert!(buffer.deliverable().is_empty());
+        assert_eq!(buffer.pending(), 1);
+    }
+
+    #[test]
+    fn test_causal_buffer_drops_duplicates_of_waiting_messages() {
+        let id = Identifier::from_bytes(vec![1]);
+        let mut clock = VClock::new();
+        clock.increment(&id);
+        let first = clock.time();
+        clock.increment(&id);
+        let second = clock.time();
+
+        // At-least-once delivery hands over the same undelivered message twice
+        let mut buffer = CausalBuffer::new();
+        buffer.push(second.clone(), "second");
+        buffer.push(second, "second");
+        assert_eq!(buffer.pending(), 1);
+
+        buffer.push(first, "first");
+        assert_eq!(buffer.deliverable(), vec!["first", "second"]);
+        assert_eq!(buffer.pending(), 0);
+    }
+}
@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+#[cfg(feature = "std")]
+use bincode::Options;
+
+use crate::{ClockError, Identifier};
+
+/// Reads a big-endian `u32` from the front of `data`
+pub(crate) fn read_u32(data: &[u8]) -> Result<(u32, &[u8]), ClockError> {
+    if data.len() < 4 {
+        return Err(ClockError::Truncated);
+    }
+    let (head, tail) = data.split_at(4);
+    Ok((u32::from_be_bytes(head.try_into().unwrap()), tail))
+}
+
+/// Reads an identifier prefixed by its big-endian `u32` length from the front of `data`
+pub(crate) fn read_identifier(data: &[u8]) -> Result<(Identifier, &[u8]), ClockError> {
+    let (len, tail) = read_u32(data)?;
+    let len = len as usize;
+    if tail.len() < len {
+        return Err(ClockError::Truncated);
+    }
+    let (id_bytes, tail) = tail.split_at(len);
+    Ok((Identifier::from_bytes(id_bytes.to_vec()), tail))
+}
+
+/// Reads a big-endian `u64` from the front of `data`
+pub(crate) fn read_u64(data: &[u8]) -> Result<(u64, &[u8]), ClockError> {
+    if data.len() < 8 {
+        return Err(ClockError::Truncated);
+    }
+    let (head, tail) = data.split_at(8);
+    Ok((u64::from_be_bytes(head.try_into().unwrap()), tail))
+}
+
+/// Appends an identifier prefixed by its big-endian `u32` length to `bytes`
+pub(crate) fn write_identifier(bytes: &mut Vec<u8>, id: &Identifier) {
+    bytes.extend_from_slice(&(id.0.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(&id.0);
+}
+
+/// The bincode configuration behind every `to_bytes`/`from_bytes` that uses bincode
+///
+/// Pinned explicitly rather than relying on bincode's defaults: integers are fixed-width and
+/// little-endian, lengths are `u64`, there is no size limit and trailing bytes are ignored.
+/// This is the layout `bincode::serialize` has always produced, so existing data still decodes.
+#[cfg(feature = "std")]
+pub(crate) fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .with_no_limit()
+        .allow_trailing_bytes()
+}
+
+/// Serializes an `AtomicU64` as its current value, for `#[serde(with = "...")]`
+///
+/// serde only implements its traits for atomics when its `std` feature is enabled; this
+/// produces the same encoding in every build.
+pub(crate) mod atomic_u64 {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(value: &AtomicU64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AtomicU64, D::Error> {
+        u64::deserialize(deserializer).map(AtomicU64::new)
+    }
+}
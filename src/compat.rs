@@ -0,0 +1,71 @@
+//! Collections and locks that come from `std` when it is available and from
+//! `hashbrown` and `spin` in `no_std` builds
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{hash_map, HashMap, HashSet};
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{hash_map, HashMap, HashSet};
+
+pub(crate) use self::fnv::Fnv1aHasher;
+
+#[cfg(feature = "std")]
+pub(crate) use std::sync::{Mutex, MutexGuard};
+#[cfg(not(feature = "std"))]
+pub(crate) use spin::{Mutex, MutexGuard};
+
+/// Locks `mutex`, blocking until it is available
+///
+/// # Panics
+///
+/// Panics if a `std` mutex was poisoned by a panicking holder. `spin` mutexes
+/// cannot be poisoned.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    #[cfg(feature = "std")]
+    {
+        mutex.lock().unwrap()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        mutex.lock()
+    }
+}
+
+/// Consumes `mutex` and returns the protected value, with the same poisoning
+/// behavior as `lock`
+pub(crate) fn into_inner<T>(mutex: Mutex<T>) -> T {
+    #[cfg(feature = "std")]
+    {
+        mutex.into_inner().unwrap()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        mutex.into_inner()
+    }
+}
+
+mod fnv {
+    use core::hash::Hasher;
+
+    /// A 64-bit FNV-1a hasher, whose output is stable across processes,
+    /// platforms and releases as long as callers feed it fixed-endian bytes
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) struct Fnv1aHasher(u64);
+
+    impl Fnv1aHasher {
+        pub(crate) fn new() -> Self {
+            Fnv1aHasher(0xcbf2_9ce4_8422_2325)
+        }
+    }
+
+    impl Hasher for Fnv1aHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+
+        fn finish(&self) -> u64 {
+            self.0
+        }
+    }
+}
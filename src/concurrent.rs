@@ -0,0 +1,341 @@
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(loom))]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::compat::{self, HashMap, Mutex};
+use crate::{ClockError, Identifier, VClock, VClockTime, Vector};
+
+/// Memory ordering used for the per-node counters of a `ConcurrentVClock`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CounterOrdering {
+    /// `Relaxed` increments and loads. Increments are never lost, since every
+    /// `fetch_add` is an atomic read-modify-write, but a snapshot may observe
+    /// increments of different nodes in any order and so need not correspond
+    /// to a state that existed at a single instant. Use it for counting-heavy
+    /// workloads that do not derive causality from snapshots taken while
+    /// writers are active.
+    Relaxed,
+    /// `AcqRel` increments and `Acquire` loads. A snapshot that observes an
+    /// increment also observes everything the incrementing thread did before
+    /// it.
+    AcquireRelease,
+    /// `SeqCst` increments and loads, giving a single total order over all
+    /// counter operations. This is the default.
+    #[default]
+    SeqCst,
+}
+
+impl CounterOrdering {
+    fn increment(self) -> Ordering {
+        match self {
+            CounterOrdering::Relaxed => Ordering::Relaxed,
+            CounterOrdering::AcquireRelease => Ordering::AcqRel,
+            CounterOrdering::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    fn load(self) -> Ordering {
+        match self {
+            CounterOrdering::Relaxed => Ordering::Relaxed,
+            CounterOrdering::AcquireRelease => Ordering::Acquire,
+            CounterOrdering::SeqCst => Ordering::SeqCst,
+        }
+    }
+}
+
+/// A lock-free vector clock over a fixed membership, incremented through
+/// shared references
+///
+/// Each member gets its own `AtomicU64`, so increments for different nodes
+/// never contend and the clock can be shared across threads behind an `Arc`.
+/// The membership is fixed at construction; incrementing an unknown node fails
+/// with `ClockError::UnknownNode`.
+#[derive(Debug)]
+pub struct ConcurrentVClock {
+    counters: HashMap<Identifier, AtomicU64>,
+    ordering: CounterOrdering,
+}
+
+impl ConcurrentVClock {
+    /// Creates a concurrent vector clock for the members of `vector`, using
+    /// `SeqCst` ordering
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{ConcurrentVClock, Identifier, Vector};
+    ///
+    /// let id = Identifier::new();
+    /// let mut members = Vector::new();
+    /// members.add(id.clone());
+    ///
+    /// let vclock = ConcurrentVClock::new(members);
+    /// assert_eq!(vclock.increment(&id), Ok(1));
+    /// assert_eq!(vclock.snapshot().0[&id], 1);
+    /// ```
+    pub fn new(vector: Vector) -> Self {
+        ConcurrentVClock::with_ordering(vector, CounterOrdering::default())
+    }
+
+    /// Creates a concurrent vector clock for the members of `vector` using the
+    /// given memory ordering
+    pub fn with_ordering(vector: Vector, ordering: CounterOrdering) -> Self {
+        ConcurrentVClock {
+            counters: vector
+                .into_hashmap()
+                .into_iter()
+                .map(|(node, counter)| (node, AtomicU64::new(counter)))
+                .collect(),
+            ordering,
+        }
+    }
+
+    /// Increments the counter for `node_id` and returns its new value
+    pub fn increment(&self, node_id: &Identifier) -> Result<u64, ClockError> {
+        let counter = self.counters.get(node_id).ok_or(ClockError::UnknownNode)?;
+        Ok(counter.fetch_add(1, self.ordering.increment()) + 1)
+    }
+
+    /// Returns the current counter for `node_id` (0 if it is not a member)
+    pub fn get(&self, node_id: &Identifier) -> u64 {
+        self.counters
+            .get(node_id)
+            .map_or(0, |counter| counter.load(self.ordering.load()))
+    }
+
+    /// Returns a snapshot of all counters
+    ///
+    /// Counters are read one at a time, so with concurrent writers the
+    /// snapshot is only guaranteed to be a consistent cut under `SeqCst` and
+    /// `AcquireRelease` for increments that happened before the call.
+    pub fn snapshot(&self) -> VClockTime {
+        VClockTime(
+            self.counters
+                .iter()
+                .map(|(node, counter)| (node.clone(), counter.load(self.ordering.load())))
+                .collect(),
+        )
+    }
+
+    /// Returns the configured memory ordering
+    pub fn ordering(&self) -> CounterOrdering {
+        self.ordering
+    }
+}
+
+/// A vector clock that can be shared across threads and updated through
+/// shared references, like `LamportClock`
+///
+/// Every operation takes a single `Mutex` around the whole clock, so
+/// operations are linearizable and a snapshot is always a consistent state,
+/// but all threads contend on the same lock. Critical sections are short (one
+/// map update, or one pass over the other clock for `merge`), which keeps this
+/// adequate for moderate contention. For fixed membership and very hot
+/// increments, `ConcurrentVClock` avoids the lock entirely.
+///
+/// In `no_std` builds the lock is a `spin` mutex, which busy-waits instead of
+/// parking the thread.
+#[derive(Debug, Default)]
+pub struct SyncVClock {
+    clock: Mutex<VClock>,
+}
+
+impl SyncVClock {
+    /// Creates a new empty shared vector clock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use logical_clocks_rs::{Identifier, SyncVClock};
+    ///
+    /// let vclock = Arc::new(SyncVClock::new());
+    /// let id = Identifier::new();
+    ///
+    /// let handle = {
+    ///     let (vclock, id) = (Arc::clone(&vclock), id.clone());
+    ///     thread::spawn(move || vclock.increment(&id))
+    /// };
+    /// handle.join().unwrap();
+    /// assert_eq!(vclock.snapshot().0[&id], 1);
+    /// ```
+    pub fn new() -> Self {
+        SyncVClock::default()
+    }
+
+    /// Wraps an existing vector clock
+    pub fn with_clock(clock: VClock) -> Self {
+        SyncVClock { clock: Mutex::new(clock) }
+    }
+
+    /// Increments the logical clock for `node`
+    pub fn increment(&self, node: &Identifier) {
+        compat::lock(&self.clock).increment(node);
+    }
+
+    /// Merges another vector clock into this one
+    pub fn merge(&self, other: &VClock) {
+        compat::lock(&self.clock).merge(other);
+    }
+
+    /// Returns a consistent snapshot of the current vector clock time
+    pub fn snapshot(&self) -> VClockTime {
+        compat::lock(&self.clock).time()
+    }
+
+    /// Consumes the wrapper and returns the underlying vector clock
+    pub fn into_inner(self) -> VClock {
+        compat::into_inner(self.clock)
+    }
+}
+
+#[cfg(all(test, feature = "std", not(loom)))]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_concurrent_increments() {
+        for ordering in [CounterOrdering::Relaxed, CounterOrdering::AcquireRelease, CounterOrdering::SeqCst] {
+            let ids = Identifier::generate(2);
+            let mut members = Vector::new();
+            members.add(ids[0].clone()).add(ids[1].clone());
+            let vclock = Arc::new(ConcurrentVClock::with_ordering(members, ordering));
+
+            let handles: Vec<_> = (0..8)
+                .map(|i| {
+                    let vclock = Arc::clone(&vclock);
+                    let id = ids[i % 2].clone();
+                    thread::spawn(move || {
+                        for _ in 0..1000 {
+                            vclock.increment(&id).unwrap();
+                        }
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            let snapshot = vclock.snapshot();
+            assert_eq!(snapshot.0[&ids[0]], 4000);
+            assert_eq!(snapshot.0[&ids[1]], 4000);
+        }
+    }
+
+    #[test]
+    fn test_concurrent_unknown_node() {
+        let vclock = ConcurrentVClock::new(Vector::new());
+        assert_eq!(vclock.increment(&Identifier::new()), Err(ClockError::UnknownNode));
+        assert_eq!(vclock.ordering(), CounterOrdering::SeqCst);
+    }
+
+    #[test]
+    fn test_sync_vclock_shared_across_threads() {
+        let ids = Identifier::generate(4);
+        let vclock = Arc::new(SyncVClock::new());
+
+        let handles: Vec<_> = ids
+            .iter()
+            .cloned()
+            .map(|id| {
+                let vclock = Arc::clone(&vclock);
+                thread::spawn(move || {
+                    for _ in 0..500 {
+                        vclock.increment(&id);
+                        let mut peer = VClock::new();
+                        peer.increment(&id);
+                        vclock.merge(&peer);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let snapshot = vclock.snapshot();
+        for id in &ids {
+            assert_eq!(snapshot.0[id], 500);
+        }
+        let vclock = Arc::try_unwrap(vclock).unwrap().into_inner();
+        assert_eq!(vclock.total_events(), 2000);
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use loom::sync::Arc;
+    use loom::thread;
+
+    use super::*;
+
+    fn members(ids: &[Identifier]) -> Vector {
+        let mut vector = Vector::new();
+        for id in ids {
+            vector.add(id.clone());
+        }
+        vector
+    }
+
+    #[test]
+    fn loom_relaxed_increments_are_not_lost() {
+        loom::model(|| {
+            let id = Identifier::from_bytes(vec![1]);
+            let vclock = Arc::new(ConcurrentVClock::with_ordering(
+                members(&[id.clone()]),
+                CounterOrdering::Relaxed,
+            ));
+
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let vclock = Arc::clone(&vclock);
+                    let id = id.clone();
+                    thread::spawn(move || {
+                        vclock.increment(&id).unwrap();
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            assert_eq!(vclock.get(&id), 2);
+        });
+    }
+
+    #[test]
+    fn loom_snapshot_observes_prior_increments() {
+        loom::model(|| {
+            let a = Identifier::from_bytes(vec![1]);
+            let b = Identifier::from_bytes(vec![2]);
+            let vclock = Arc::new(ConcurrentVClock::with_ordering(
+                members(&[a.clone(), b.clone()]),
+                CounterOrdering::AcquireRelease,
+            ));
+
+            let writer = {
+                let vclock = Arc::clone(&vclock);
+                let (a, b) = (a.clone(), b.clone());
+                thread::spawn(move || {
+                    vclock.increment(&a).unwrap();
+                    vclock.increment(&b).unwrap();
+                })
+            };
+
+            // Seeing b's increment implies seeing a's, which happened before it
+            if vclock.get(&b) == 1 {
+                assert_eq!(vclock.get(&a), 1);
+            }
+            writer.join().unwrap();
+
+            let snapshot = vclock.snapshot();
+            assert_eq!(snapshot.0[&a], 1);
+            assert_eq!(snapshot.0[&b], 1);
+        });
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Identifier, VClock};
+
+/// A grow-only counter CRDT
+///
+/// Each node only ever adds to its own entry, so the state is a vector clock
+/// whose entries may advance by more than one, and merging is the vector clock
+/// merge (component-wise maximum). The counter's value is the sum of all
+/// entries. Merging is commutative, associative and idempotent, so replicas
+/// converge whatever order they exchange state in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GCounter {
+    clock: VClock,
+}
+
+impl GCounter {
+    /// Creates a new counter with value 0
+    pub fn new() -> Self {
+        GCounter::default()
+    }
+
+    /// Adds `by` to the entry for `node`
+    ///
+    /// Only `node` itself should increment its entry; otherwise concurrent
+    /// increments of the same entry are lost on merge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{GCounter, Identifier};
+    ///
+    /// let node = Identifier::new();
+    /// let mut counter = GCounter::new();
+    /// counter.increment(&node, 3);
+    /// counter.increment(&node, 2);
+    /// assert_eq!(counter.value(), 5);
+    /// ```
+    pub fn increment(&mut self, node: &Identifier, by: u64) {
+        *self.clock.vector.entry(node.clone()).or_insert(0) += by;
+    }
+
+    /// Returns the counter's value, the sum of every node's entry
+    pub fn value(&self) -> u64 {
+        self.clock.total_events()
+    }
+
+    /// Merges another replica's state into this one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{GCounter, Identifier};
+    ///
+    /// let mut a = GCounter::new();
+    /// a.increment(&Identifier::new(), 2);
+    /// let mut b = GCounter::new();
+    /// b.increment(&Identifier::new(), 3);
+    ///
+    /// a.merge(&b);
+    /// assert_eq!(a.value(), 5);
+    /// ```
+    pub fn merge(&mut self, other: &GCounter) {
+        self.clock.merge(&other.clock);
+    }
+}
+
+/// A positive-negative counter CRDT supporting both increments and decrements
+///
+/// It pairs two `GCounter`s, one counting increments (P) and one counting
+/// decrements (N), and its value is `P - N`. Merging merges both halves, so it
+/// inherits the convergence of `GCounter`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PNCounter {
+    increments: GCounter,
+    decrements: GCounter,
+}
+
+impl PNCounter {
+    /// Creates a new counter with value 0
+    pub fn new() -> Self {
+        PNCounter::default()
+    }
+
+    /// Adds one to the counter on behalf of `node`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, PNCounter};
+    ///
+    /// let node = Identifier::new();
+    /// let mut counter = PNCounter::new();
+    /// counter.increment(&node);
+    /// counter.decrement(&node);
+    /// counter.decrement(&node);
+    /// assert_eq!(counter.value(), -1);
+    /// ```
+    pub fn increment(&mut self, node: &Identifier) {
+        self.increments.increment(node, 1);
+    }
+
+    /// Subtracts one from the counter on behalf of `node`
+    pub fn decrement(&mut self, node: &Identifier) {
+        self.decrements.increment(node, 1);
+    }
+
+    /// Returns the counter's value, total increments minus total decrements
+    pub fn value(&self) -> i64 {
+        self.increments.value() as i64 - self.decrements.value() as i64
+    }
+
+    /// Merges another replica's state into this one
+    pub fn merge(&mut self, other: &PNCounter) {
+        self.increments.merge(&other.increments);
+        self.decrements.merge(&other.decrements);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn replicas() -> (GCounter, GCounter, GCounter) {
+        let ids = Identifier::generate(3);
+        let mut a = GCounter::new();
+        a.increment(&ids[0], 4);
+        let mut b = a.clone();
+        b.increment(&ids[1], 2);
+        a.increment(&ids[0], 1);
+        let mut c = GCounter::new();
+        c.increment(&ids[2], 7);
+        (a, b, c)
+    }
+
+    #[test]
+    fn test_gcounter_merge_is_idempotent() {
+        let (mut a, b, _) = replicas();
+        a.merge(&b);
+        let once = a.clone();
+        a.merge(&b);
+        a.merge(&once);
+        assert_eq!(a, once);
+        assert_eq!(a.value(), 5 + 2);
+    }
+
+    #[test]
+    fn test_gcounter_merge_is_commutative() {
+        let (a, b, c) = replicas();
+
+        let mut abc = a.clone();
+        abc.merge(&b);
+        abc.merge(&c);
+        let mut cba = c.clone();
+        cba.merge(&b);
+        cba.merge(&a);
+
+        assert_eq!(abc, cba);
+        assert_eq!(abc.value(), 5 + 2 + 7);
+    }
+
+    #[test]
+    fn test_pncounter_converges_in_any_merge_order() {
+        let ids = Identifier::generate(3);
+        let mut replicas = [PNCounter::new(), PNCounter::new(), PNCounter::new()];
+        for _ in 0..5 {
+            replicas[0].increment(&ids[0]);
+        }
+        replicas[1].decrement(&ids[1]);
+        replicas[1].decrement(&ids[1]);
+        replicas[2].increment(&ids[2]);
+        replicas[2].decrement(&ids[2]);
+        replicas[2].decrement(&ids[2]);
+
+        let orders = [[0, 1, 2], [2, 1, 0], [1, 2, 0], [1, 0, 2]];
+        let results: Vec<PNCounter> = orders
+            .iter()
+            .map(|order| {
+                let mut merged = PNCounter::new();
+                for &i in order {
+                    merged.merge(&replicas[i]);
+                }
+                // Re-merging is harmless
+                merged.merge(&replicas[order[0]]);
+                merged
+            })
+            .collect();
+
+        for result in &results {
+            assert_eq!(result, &results[0]);
+            assert_eq!(result.value(), 5 - 2 + 1 - 2);
+        }
+    }
+}
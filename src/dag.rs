@@ -0,0 +1,153 @@
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::compat::HashMap;
+use crate::{Identifier, VClockTime};
+
+/// Key of an event in a `CausalDag`: the node that produced it and that
+/// node's counter for the event
+pub type EventId = (Identifier, u64);
+
+#[derive(Debug, Clone)]
+struct DagEntry {
+    clock: VClockTime,
+    parents: Vec<EventId>,
+}
+
+/// A causal history DAG of individual events with explicit parent links
+///
+/// Each event is recorded with the `VClockTime` it was stamped with, and its
+/// parents are the immediate predecessors among the events already recorded:
+/// the events that happened before it and are not themselves covered by a
+/// later predecessor. Events must be added in an order consistent with
+/// happens-before (e.g. as produced by `causal_sort`), otherwise a parent added
+/// after its child will not be linked.
+#[derive(Debug, Clone, Default)]
+pub struct CausalDag {
+    events: HashMap<EventId, DagEntry>,
+}
+
+impl CausalDag {
+    /// Creates a new empty DAG
+    pub fn new() -> Self {
+        CausalDag::default()
+    }
+
+    /// Records an event produced by `id` with the given clock and returns its
+    /// key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{CausalDag, Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// let mut dag = CausalDag::new();
+    ///
+    /// vclock.increment(&id);
+    /// let first = dag.add_event(id.clone(), vclock.time());
+    /// vclock.increment(&id);
+    /// let second = dag.add_event(id.clone(), vclock.time());
+    ///
+    /// assert_eq!(dag.parents(&second), Some(&[first][..]));
+    /// ```
+    pub fn add_event(&mut self, id: Identifier, clock: VClockTime) -> EventId {
+        let predecessors: Vec<(&EventId, &DagEntry)> = self
+            .events
+            .iter()
+            .filter(|(_, entry)| entry.clock.partial_cmp(&clock) == Some(Ordering::Less))
+            .collect();
+
+        let mut parents: Vec<EventId> = predecessors
+            .iter()
+            .filter(|(_, entry)| {
+                !predecessors
+                    .iter()
+                    .any(|(_, other)| entry.clock.partial_cmp(&other.clock) == Some(Ordering::Less))
+            })
+            .map(|(key, _)| (*key).clone())
+            .collect();
+        parents.sort();
+
+        let key = (id.clone(), *clock.0.get(&id).unwrap_or(&0));
+        self.events.insert(key.clone(), DagEntry { clock, parents });
+        key
+    }
+
+    /// Returns the immediate predecessors of an event
+    pub fn parents(&self, event: &EventId) -> Option<&[EventId]> {
+        self.events.get(event).map(|entry| entry.parents.as_slice())
+    }
+
+    /// Returns the clock an event was recorded with
+    pub fn clock(&self, event: &EventId) -> Option<&VClockTime> {
+        self.events.get(event).map(|entry| &entry.clock)
+    }
+
+    /// Returns every event reachable through parent links, excluding the event
+    /// itself
+    pub fn ancestors(&self, event: &EventId) -> BTreeSet<EventId> {
+        let mut visited = BTreeSet::new();
+        let mut stack: Vec<&EventId> = self.parents(event).unwrap_or(&[]).iter().collect();
+
+        while let Some(next) = stack.pop() {
+            if visited.insert(next.clone()) {
+                stack.extend(self.parents(next).unwrap_or(&[]));
+            }
+        }
+        visited
+    }
+
+    /// Returns the number of recorded events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if no events have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VClock;
+
+    #[test]
+    fn test_dag_ancestry() {
+        let a = Identifier::from_bytes(vec![1]);
+        let b = Identifier::from_bytes(vec![2]);
+        let mut dag = CausalDag::new();
+
+        // a1 -> a2, a1 -> b1, (a2, b1) -> b2
+        let mut clock_a = VClock::new();
+
+        clock_a.increment(&a);
+        let a1 = dag.add_event(a.clone(), clock_a.time());
+        let mut clock_b = clock_a.clone();
+
+        clock_a.increment(&a);
+        let a2 = dag.add_event(a.clone(), clock_a.time());
+
+        clock_b.increment(&b);
+        let b1 = dag.add_event(b.clone(), clock_b.time());
+
+        clock_b.merge(&clock_a);
+        clock_b.increment(&b);
+        let b2 = dag.add_event(b.clone(), clock_b.time());
+
+        assert_eq!(dag.len(), 4);
+        assert_eq!(dag.parents(&a1), Some(&[][..]));
+        assert_eq!(dag.parents(&a2), Some(&[a1.clone()][..]));
+        assert_eq!(dag.parents(&b1), Some(&[a1.clone()][..]));
+        assert_eq!(dag.parents(&b2), Some(&[a2.clone(), b1.clone()][..]));
+
+        assert_eq!(dag.ancestors(&b2), BTreeSet::from([a1.clone(), a2.clone(), b1.clone()]));
+        assert_eq!(dag.ancestors(&b1), BTreeSet::from([a1.clone()]));
+        assert!(dag.ancestors(&a1).is_empty());
+        assert!(dag.ancestors(&(b.clone(), 9)).is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use serde::{Deserialize, Serialize};
+
+use crate::{ClockError, Identifier, VClock};
+
+/// A vector clock over a dense space of node indices, backed by a `Vec<u64>`
+///
+/// When nodes are numbered `0..n`, indexing a vector is both faster and far
+/// more compact than hashing identifiers. Merge and comparison semantics match
+/// `VClock`: a missing index is equivalent to a zero counter. Use `from_sparse`
+/// and `to_sparse` with a shared roster to convert between the two forms.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DenseVClock {
+    counters: Vec<u64>,
+}
+
+impl DenseVClock {
+    /// Creates a new empty dense vector clock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::DenseVClock;
+    ///
+    /// let vclock = DenseVClock::new();
+    /// assert_eq!(vclock.get(0), 0);
+    /// ```
+    pub fn new() -> Self {
+        DenseVClock::default()
+    }
+
+    /// Increments the counter for the node at `index`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::DenseVClock;
+    ///
+    /// let mut vclock = DenseVClock::new();
+    /// vclock.increment(2);
+    /// assert_eq!(vclock.get(2), 1);
+    /// ```
+    pub fn increment(&mut self, index: usize) {
+        if self.counters.len() <= index {
+            self.counters.resize(index + 1, 0);
+        }
+        self.counters[index] += 1;
+    }
+
+    /// Returns the counter for the node at `index` (0 if absent)
+    pub fn get(&self, index: usize) -> u64 {
+        self.counters.get(index).copied().unwrap_or(0)
+    }
+
+    /// Merges another dense vector clock into this one
+    pub fn merge(&mut self, other: &DenseVClock) {
+        if self.counters.len() < other.counters.len() {
+            self.counters.resize(other.counters.len(), 0);
+        }
+        for (counter, &other_counter) in self.counters.iter_mut().zip(&other.counters) {
+            *counter = (*counter).max(other_counter);
+        }
+    }
+
+    /// Checks if this vector clock happened before another vector clock
+    pub fn happened_before(&self, other: &DenseVClock) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Less)
+    }
+
+    /// Builds a dense clock from a sparse one, numbering nodes by their
+    /// position in `roster`
+    ///
+    /// Fails with `ClockError::UnknownNode` if the clock has a non-zero entry
+    /// for a node that is not in the roster.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{DenseVClock, Identifier, VClock};
+    ///
+    /// let roster = Identifier::generate(3);
+    /// let mut sparse = VClock::new();
+    /// sparse.increment(&roster[1]);
+    ///
+    /// let dense = DenseVClock::from_sparse(&sparse, &roster).unwrap();
+    /// assert_eq!(dense.get(1), 1);
+    /// assert_eq!(dense.to_sparse(&roster).unwrap(), sparse);
+    /// ```
+    pub fn from_sparse(clock: &VClock, roster: &[Identifier]) -> Result<Self, ClockError> {
+        let mut dense = DenseVClock {
+            counters: roster.iter().map(|node| *clock.vector.get(node).unwrap_or(&0)).collect(),
+        };
+
+        let covered = dense.counters.iter().filter(|&&counter| counter > 0).count();
+        if covered != clock.vector.values().filter(|&&counter| counter > 0).count() {
+            return Err(ClockError::UnknownNode);
+        }
+
+        dense.trim();
+        Ok(dense)
+    }
+
+    /// Converts this dense clock back to a sparse `VClock` using `roster`
+    ///
+    /// Fails with `ClockError::UnknownNode` if a non-zero index has no roster
+    /// entry.
+    pub fn to_sparse(&self, roster: &[Identifier]) -> Result<VClock, ClockError> {
+        let mut clock = VClock::new();
+        for (index, &counter) in self.counters.iter().enumerate() {
+            if counter == 0 {
+                continue;
+            }
+            let node = roster.get(index).ok_or(ClockError::UnknownNode)?;
+            clock.vector.insert(node.clone(), counter);
+        }
+        Ok(clock)
+    }
+
+    fn trim(&mut self) {
+        while self.counters.last() == Some(&0) {
+            self.counters.pop();
+        }
+    }
+}
+
+impl PartialEq for DenseVClock {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl Eq for DenseVClock {}
+
+impl PartialOrd for DenseVClock {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let mut is_less = false;
+        let mut is_greater = false;
+
+        for index in 0..self.counters.len().max(other.counters.len()) {
+            match self.get(index).cmp(&other.get(index)) {
+                Ordering::Less => is_less = true,
+                Ordering::Greater => is_greater = true,
+                Ordering::Equal => {}
+            }
+
+            if is_less && is_greater {
+                return None;
+            }
+        }
+
+        match (is_less, is_greater) {
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dense_matches_sparse() {
+        let roster = Identifier::generate(8);
+
+        let mut a = VClock::new();
+        let mut b = VClock::new();
+        for (i, id) in roster.iter().enumerate() {
+            for _ in 0..i {
+                a.increment(id);
+            }
+            for _ in 0..(8 - i) {
+                b.increment(id);
+            }
+        }
+        let mut c = a.clone();
+        c.merge(&b);
+
+        let dense_a = DenseVClock::from_sparse(&a, &roster).unwrap();
+        let dense_b = DenseVClock::from_sparse(&b, &roster).unwrap();
+        let dense_c = DenseVClock::from_sparse(&c, &roster).unwrap();
+
+        // Comparisons agree with the sparse representation
+        for (sparse_x, dense_x) in [(&a, &dense_a), (&b, &dense_b), (&c, &dense_c)] {
+            for (sparse_y, dense_y) in [(&a, &dense_a), (&b, &dense_b), (&c, &dense_c)] {
+                assert_eq!(
+                    sparse_x.time().partial_cmp(&sparse_y.time()),
+                    dense_x.partial_cmp(dense_y)
+                );
+                assert_eq!(sparse_x.happened_before(sparse_y), dense_x.happened_before(dense_y));
+            }
+        }
+
+        // Merging agrees and round-trips
+        let mut merged = dense_a.clone();
+        merged.merge(&dense_b);
+        assert_eq!(merged, dense_c);
+        assert_eq!(merged.to_sparse(&roster).unwrap(), c);
+
+        // The dense form is much smaller than the sparse one
+        let dense_size = bincode::serialize(&dense_c).unwrap().len();
+        let sparse_size = c.time().to_bytes().unwrap().len();
+        assert!(dense_size * 3 < sparse_size);
+    }
+
+    #[test]
+    fn test_dense_unknown_node() {
+        let roster = Identifier::generate(2);
+        let mut sparse = VClock::new();
+        sparse.increment(&Identifier::new());
+
+        assert_eq!(DenseVClock::from_sparse(&sparse, &roster), Err(ClockError::UnknownNode));
+
+        let mut dense = DenseVClock::new();
+        dense.increment(5);
+        assert_eq!(dense.to_sparse(&roster), Err(ClockError::UnknownNode));
+    }
+}
@@ -0,0 +1,111 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::vclock::covering_edges;
+use crate::VClockTime;
+
+/// Renders the Hasse diagram of a set of named snapshots in Graphviz DOT
+/// format
+///
+/// Only covering edges are drawn: an edge `a -> b` means `a` happened before
+/// `b` with no other snapshot in between, which is the transitive reduction of
+/// the happened-before relation. Snapshots are layered by the length of the
+/// longest causal chain leading to them; snapshots sharing a layer are
+/// mutually concurrent and are grouped with `rank=same`.
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::{to_hasse_dot, Identifier, VClock};
+///
+/// let id = Identifier::new();
+/// let mut vclock = VClock::new();
+/// vclock.increment(&id);
+/// let a = vclock.time();
+/// vclock.increment(&id);
+/// let b = vclock.time();
+///
+/// let dot = to_hasse_dot(&[("a", &a), ("b", &b)]);
+/// assert!(dot.contains("\"a\" -> \"b\";"));
+/// ```
+pub fn to_hasse_dot(events: &[(&str, &VClockTime)]) -> String {
+    let times: Vec<&VClockTime> = events.iter().map(|(_, time)| *time).collect();
+    let edges = covering_edges(&times);
+
+    // Longest-path layering; edges always point from a lower to a higher layer
+    let mut layer = vec![0usize; events.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &(from, to) in &edges {
+            if layer[to] < layer[from] + 1 {
+                layer[to] = layer[from] + 1;
+                changed = true;
+            }
+        }
+    }
+
+    let mut layers: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (index, &rank) in layer.iter().enumerate() {
+        layers.entry(rank).or_default().push(index);
+    }
+
+    let name = |index: usize| format!("\"{}\"", events[index].0.replace('"', "\\\""));
+
+    let mut dot = String::from("digraph causal {\n");
+    for index in 0..events.len() {
+        dot.push_str(&format!("    {};\n", name(index)));
+    }
+    for (from, to) in edges {
+        dot.push_str(&format!("    {} -> {};\n", name(from), name(to)));
+    }
+    for members in layers.values().filter(|members| members.len() > 1) {
+        let group: Vec<String> = members.iter().map(|&index| name(index)).collect();
+        dot.push_str(&format!("    {{ rank=same; {}; }}\n", group.join("; ")));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{Identifier, VClock};
+
+    #[test]
+    fn test_hasse_dot_diamond() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut top = VClock::new();
+        top.increment(&id1);
+        let mut left = top.clone();
+        left.increment(&id1);
+        let mut right = top.clone();
+        right.increment(&id2);
+        let mut bottom = left.clone();
+        bottom.merge(&right);
+        bottom.increment(&id2);
+
+        let (top, left, right, bottom) = (top.time(), left.time(), right.time(), bottom.time());
+        let events = [("bottom", &bottom), ("left", &left), ("top", &top), ("right", &right)];
+
+        let times: Vec<&VClockTime> = events.iter().map(|(_, time)| *time).collect();
+        let mut edges = covering_edges(&times);
+        edges.sort();
+        // The transitive edge top -> bottom is removed
+        assert_eq!(edges, vec![(1, 0), (2, 1), (2, 3), (3, 0)]);
+
+        let dot = to_hasse_dot(&events);
+        assert!(dot.starts_with("digraph causal {\n"));
+        assert!(dot.contains("    \"top\" -> \"left\";\n"));
+        assert!(dot.contains("    \"top\" -> \"right\";\n"));
+        assert!(dot.contains("    \"left\" -> \"bottom\";\n"));
+        assert!(dot.contains("    \"right\" -> \"bottom\";\n"));
+        assert!(!dot.contains("\"top\" -> \"bottom\""));
+        assert!(dot.contains("    { rank=same; \"left\"; \"right\"; }\n"));
+    }
+}
@@ -0,0 +1,43 @@
+use alloc::string::String;
+use core::fmt;
+
+/// Errors returned by clock encoding, decoding and validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClockError {
+    /// The input ended before a complete value could be decoded
+    Truncated,
+    /// The input contained bytes after the last decoded value
+    TrailingBytes,
+    /// An encoded entry carried an unknown tag byte
+    InvalidTag(u8),
+    /// An identifier or node index is not part of the known membership
+    UnknownNode,
+    /// An identifier did not have the length required by the encoding
+    InvalidIdentifierLength(usize),
+    /// Text could not be parsed as an identifier
+    InvalidIdentifier,
+    /// A remote time was further ahead of the local time source than allowed,
+    /// by the given number of milliseconds
+    ClockSkew(u64),
+    /// A value could not be serialized or deserialized, with the underlying
+    /// error message
+    Serialization(String),
+}
+
+impl fmt::Display for ClockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClockError::Truncated => write!(f, "input ended before a complete value was decoded"),
+            ClockError::TrailingBytes => write!(f, "unexpected bytes after the encoded value"),
+            ClockError::InvalidTag(tag) => write!(f, "invalid entry tag {}", tag),
+            ClockError::UnknownNode => write!(f, "node is not part of the known membership"),
+            ClockError::InvalidIdentifierLength(len) => write!(f, "invalid identifier length {}", len),
+            ClockError::InvalidIdentifier => write!(f, "text is not a valid identifier"),
+            ClockError::ClockSkew(drift) => write!(f, "remote time is {} ms ahead of the local clock", drift),
+            ClockError::Serialization(message) => write!(f, "serialization failed: {}", message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ClockError {}
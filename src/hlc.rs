@@ -0,0 +1,292 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::codec::{read_u32, read_u64};
+use crate::ClockError;
+
+/// Default bound, in milliseconds, on how far a remote physical time may run
+/// ahead of the local time source before `try_update` rejects it
+pub const DEFAULT_MAX_DRIFT_MS: u64 = 500;
+
+/// A hybrid logical time: a physical millisecond component and a logical
+/// counter that orders events sharing the same millisecond
+///
+/// Times order by physical component first and logical counter second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub struct HlcTime {
+    /// Milliseconds since the Unix epoch, as reported by the time source
+    pub physical: u64,
+    /// Counter distinguishing events within the same physical millisecond
+    pub logical: u32,
+}
+
+impl HlcTime {
+    /// Serializes the time to 12 bytes: the big-endian `u64` physical
+    /// component followed by the big-endian `u32` logical counter
+    ///
+    /// The byte order matches the `Ord` implementation, so encoded times can be
+    /// compared and sorted as raw bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::HlcTime;
+    ///
+    /// let time = HlcTime { physical: 1_700_000_000_000, logical: 3 };
+    /// let bytes = time.to_bytes();
+    /// assert_eq!(bytes.len(), 12);
+    /// assert_eq!(HlcTime::from_bytes(&bytes).unwrap(), time);
+    /// ```
+    pub fn to_bytes(&self) -> [u8; 12] {
+        let mut bytes = [0; 12];
+        bytes[..8].copy_from_slice(&self.physical.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.logical.to_be_bytes());
+        bytes
+    }
+
+    /// Deserializes a time from the 12-byte layout produced by `to_bytes`
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ClockError> {
+        let (physical, rest) = read_u64(data)?;
+        let (logical, rest) = read_u32(rest)?;
+        if !rest.is_empty() {
+            return Err(ClockError::TrailingBytes);
+        }
+        Ok(HlcTime { physical, logical })
+    }
+
+    /// Returns the time one logical tick later, carrying into the physical
+    /// component if the logical counter is exhausted
+    fn tick(self) -> Self {
+        match self.logical.checked_add(1) {
+            Some(logical) => HlcTime { physical: self.physical, logical },
+            None => HlcTime { physical: self.physical + 1, logical: 0 },
+        }
+    }
+}
+
+/// A Hybrid Logical Clock (Kulkarni et al.)
+///
+/// Like a `LamportClock` it never goes backwards and moves past every time it
+/// witnesses, but it keeps its physical component close to the time source so
+/// stamps remain meaningful as wall-clock times. The time source is pluggable,
+/// which lets tests drive the clock with a fake time.
+///
+/// The logical counter is bounded: once it is exhausted the clock carries into
+/// the physical component instead of overflowing. Remote times are bounded by
+/// `try_update`, which rejects times too far ahead of the local time source.
+pub struct HybridClock {
+    source: Box<dyn Fn() -> u64 + Send + Sync>,
+    last: Mutex<HlcTime>,
+    max_drift: u64,
+}
+
+impl HybridClock {
+    /// Creates a hybrid clock driven by the system time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::HybridClock;
+    ///
+    /// let clock = HybridClock::new();
+    /// let first = clock.now();
+    /// assert!(clock.now() > first);
+    /// ```
+    pub fn new() -> Self {
+        HybridClock::with_time_source(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64)
+        })
+    }
+
+    /// Creates a hybrid clock driven by `source`, which returns the current
+    /// time in milliseconds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{HlcTime, HybridClock};
+    ///
+    /// let clock = HybridClock::with_time_source(|| 1000);
+    /// assert_eq!(clock.now(), HlcTime { physical: 1000, logical: 0 });
+    /// assert_eq!(clock.now(), HlcTime { physical: 1000, logical: 1 });
+    /// ```
+    pub fn with_time_source<F: Fn() -> u64 + Send + Sync + 'static>(source: F) -> Self {
+        HybridClock {
+            source: Box::new(source),
+            last: Mutex::new(HlcTime::default()),
+            max_drift: DEFAULT_MAX_DRIFT_MS,
+        }
+    }
+
+    /// Sets how far, in milliseconds, a remote time may be ahead of the local
+    /// time source before `try_update` rejects it
+    pub fn with_max_drift(mut self, max_drift: u64) -> Self {
+        self.max_drift = max_drift;
+        self
+    }
+
+    /// Records a local or send event and returns its time
+    pub fn now(&self) -> HlcTime {
+        let physical = (self.source)();
+        let mut last = self.last.lock().unwrap();
+        *last = if physical > last.physical {
+            HlcTime { physical, logical: 0 }
+        } else {
+            last.tick()
+        };
+        *last
+    }
+
+    /// Records the receipt of a message stamped `remote` and returns the new
+    /// local time, which is later than both `remote` and every earlier local
+    /// time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{HlcTime, HybridClock};
+    ///
+    /// let clock = HybridClock::with_time_source(|| 1000);
+    /// let remote = HlcTime { physical: 1200, logical: 4 };
+    /// assert_eq!(clock.update(&remote), HlcTime { physical: 1200, logical: 5 });
+    /// ```
+    pub fn update(&self, remote: &HlcTime) -> HlcTime {
+        let physical = (self.source)();
+        let mut last = self.last.lock().unwrap();
+        let max_physical = physical.max(last.physical).max(remote.physical);
+
+        *last = if max_physical == last.physical && max_physical == remote.physical {
+            HlcTime { physical: max_physical, logical: last.logical.max(remote.logical) }.tick()
+        } else if max_physical == last.physical {
+            last.tick()
+        } else if max_physical == remote.physical {
+            remote.tick()
+        } else {
+            HlcTime { physical: max_physical, logical: 0 }
+        };
+        *last
+    }
+
+    /// Like `update`, but fails with `ClockError::ClockSkew` without touching
+    /// the clock if `remote` is more than the maximum drift ahead of the local
+    /// time source
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{ClockError, HlcTime, HybridClock};
+    ///
+    /// let clock = HybridClock::with_time_source(|| 1000).with_max_drift(100);
+    /// let remote = HlcTime { physical: 1500, logical: 0 };
+    /// assert_eq!(clock.try_update(&remote), Err(ClockError::ClockSkew(500)));
+    /// ```
+    pub fn try_update(&self, remote: &HlcTime) -> Result<HlcTime, ClockError> {
+        let drift = remote.physical.saturating_sub((self.source)());
+        if drift > self.max_drift {
+            return Err(ClockError::ClockSkew(drift));
+        }
+        Ok(self.update(remote))
+    }
+
+    /// Returns the last time issued by this clock without advancing it
+    pub fn last(&self) -> HlcTime {
+        *self.last.lock().unwrap()
+    }
+}
+
+impl Default for HybridClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for HybridClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HybridClock")
+            .field("last", &self.last())
+            .field("max_drift", &self.max_drift)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn fake_clock(start: u64) -> (HybridClock, Arc<AtomicU64>) {
+        let millis = Arc::new(AtomicU64::new(start));
+        let source = Arc::clone(&millis);
+        (HybridClock::with_time_source(move || source.load(Ordering::SeqCst)), millis)
+    }
+
+    #[test]
+    fn test_hlc_now_tracks_time_source() {
+        let (clock, millis) = fake_clock(100);
+        assert_eq!(clock.now(), HlcTime { physical: 100, logical: 0 });
+        assert_eq!(clock.now(), HlcTime { physical: 100, logical: 1 });
+
+        millis.store(150, Ordering::SeqCst);
+        assert_eq!(clock.now(), HlcTime { physical: 150, logical: 0 });
+
+        // The time source going backwards never moves the clock backwards
+        millis.store(120, Ordering::SeqCst);
+        assert_eq!(clock.now(), HlcTime { physical: 150, logical: 1 });
+    }
+
+    #[test]
+    fn test_hlc_update() {
+        let (clock, millis) = fake_clock(100);
+        clock.now();
+
+        // Remote ahead: adopt its physical component
+        let remote = HlcTime { physical: 300, logical: 7 };
+        assert_eq!(clock.update(&remote), HlcTime { physical: 300, logical: 8 });
+
+        // Same physical component on both sides: take the larger logical counter
+        let remote = HlcTime { physical: 300, logical: 2 };
+        assert_eq!(clock.update(&remote), HlcTime { physical: 300, logical: 9 });
+
+        // Time source ahead of both: reset the logical counter
+        millis.store(400, Ordering::SeqCst);
+        assert_eq!(clock.update(&remote), HlcTime { physical: 400, logical: 0 });
+    }
+
+    #[test]
+    fn test_hlc_bounds() {
+        let (clock, _) = fake_clock(1000);
+        let clock = clock.with_max_drift(50);
+
+        assert_eq!(
+            clock.try_update(&HlcTime { physical: 1100, logical: 0 }),
+            Err(ClockError::ClockSkew(100))
+        );
+        assert_eq!(clock.last(), HlcTime::default());
+        assert!(clock.try_update(&HlcTime { physical: 1050, logical: 0 }).is_ok());
+
+        // An exhausted logical counter carries into the physical component
+        let remote = HlcTime { physical: 1050, logical: u32::MAX };
+        assert_eq!(clock.update(&remote), HlcTime { physical: 1051, logical: 0 });
+    }
+
+    #[test]
+    fn test_hlc_time_bytes_preserve_order() {
+        let times = [
+            HlcTime { physical: 1, logical: u32::MAX },
+            HlcTime { physical: 2, logical: 0 },
+            HlcTime { physical: 2, logical: 1 },
+        ];
+        for pair in times.windows(2) {
+            assert!(pair[0].to_bytes() < pair[1].to_bytes());
+        }
+        assert_eq!(HlcTime::from_bytes(&[0; 11]), Err(ClockError::Truncated));
+        assert_eq!(HlcTime::from_bytes(&[0; 13]), Err(ClockError::TrailingBytes));
+    }
+}
@@ -1,36 +1,664 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hasher;
+use core::str::FromStr;
 use base64::{engine::general_purpose, Engine};
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
+use crate::compat::{Fnv1aHasher, HashMap};
+use crate::ClockError;
+
 /// Represents identifier
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Identifier(pub Vec<u8>);
 
 impl Identifier {
     /// Creates a new Identifier with a random UUID
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
         Identifier(Uuid::new_v4().as_bytes().to_vec())
     }
 
+    /// Returns the nil Identifier: the all-zero 16-byte nil UUID
+    ///
+    /// Unlike `Identifier::default()`, which generates a random UUID, `nil()`
+    /// is always the same value. Use it only as a placeholder where the
+    /// identity is irrelevant, never as the id of a real node. It is not a
+    /// `const fn` because `Identifier` owns a heap-allocated `Vec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportTime};
+    ///
+    /// let time = LamportTime(10, Identifier::nil());
+    /// assert_eq!(time.1, Identifier::nil());
+    /// assert_eq!(Identifier::nil().to_string(), "00000000-0000-0000-0000-000000000000");
+    /// ```
+    pub fn nil() -> Self {
+        Identifier(Uuid::nil().as_bytes().to_vec())
+    }
+
     /// Creates an Identifier from a byte vector
     pub fn from_bytes(bytes: Vec<u8>) -> Self {
         Identifier(bytes)
     }
 
-    /// Converts the Identifier to a string
-    pub fn to_string(&self) -> String {
-        if let Ok(uuid) = Uuid::from_slice(&self.0) {
-            uuid.to_string()
-        } else {
-            let b64 = general_purpose::STANDARD.encode(&self.0);
-            println!("{}", b64);
-            b64 
+    /// Returns the identifier as a UUID if it is exactly 16 bytes long
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// assert!(Identifier::new().as_uuid().is_some());
+    /// assert!(Identifier::from_bytes(vec![1, 2, 3]).as_uuid().is_none());
+    /// ```
+    pub fn as_uuid(&self) -> Option<Uuid> {
+        Uuid::from_slice(&self.0).ok()
+    }
+
+    /// Creates a content-addressed Identifier from the SHA-256 digest of `data`
+    ///
+    /// The same input always yields the same 32-byte identifier, which makes it
+    /// suitable for deriving node ids from material such as a public key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// let id = Identifier::from_content(b"node public key");
+    /// assert_eq!(id, Identifier::from_content(b"node public key"));
+    /// ```
+    #[cfg(feature = "hashing")]
+    pub fn from_content(data: &[u8]) -> Self {
+        use sha2::{Digest, Sha256};
+        Identifier(Sha256::digest(data).to_vec())
+    }
+
+    /// Creates a deterministic Identifier from a name using a UUID v5
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// assert_eq!(Identifier::from_name("node-0"), Identifier::from_name("node-0"));
+    /// ```
+    pub fn from_name(name: &str) -> Self {
+        Identifier(Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).as_bytes().to_vec())
+    }
+
+    /// Parses an Identifier from a UUID in URN form, e.g.
+    /// `urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8`
+    ///
+    /// The `urn:uuid:` prefix is matched case-insensitively. The identifier
+    /// holds the 16 UUID bytes, so it formats back as the plain UUID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// let id = Identifier::from_urn("urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    /// assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    /// ```
+    pub fn from_urn(urn: &str) -> Result<Self, ClockError> {
+        const PREFIX: &str = "urn:uuid:";
+        let uuid = urn
+            .get(..PREFIX.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(PREFIX))
+            .and_then(|_| Uuid::try_parse(&urn[PREFIX.len()..]).ok())
+            .ok_or(ClockError::InvalidIdentifier)?;
+        Ok(Identifier(uuid.as_bytes().to_vec()))
+    }
+
+    /// Generates `n` distinct random Identifiers
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// let ids = Identifier::generate(3);
+    /// assert_eq!(ids.len(), 3);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn generate(n: usize) -> Vec<Identifier> {
+        (0..n).map(|_| Identifier::new()).collect()
+    }
+
+    /// Generates `n` reproducible Identifiers named `{prefix}-0` through
+    /// `{prefix}-{n-1}` with `from_name`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// let ids = Identifier::generate_named("node", 2);
+    /// assert_eq!(ids[1], Identifier::from_name("node-1"));
+    /// ```
+    pub fn generate_named(prefix: &str, n: usize) -> Vec<Identifier> {
+        (0..n)
+            .map(|i| Identifier::from_name(&format!("{}-{}", prefix, i)))
+            .collect()
+    }
+
+    /// Maps the Identifier to one of `num_shards` shards
+    ///
+    /// The shard is derived from a 64-bit FNV-1a hash of the bytes, which is
+    /// stable across processes, platforms and releases, so it can be used as a
+    /// persistent routing key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_shards` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// let id = Identifier::new();
+    /// assert!(id.shard(16) < 16);
+    /// assert_eq!(id.shard(16), id.shard(16));
+    /// ```
+    pub fn shard(&self, num_shards: u32) -> u32 {
+        assert!(num_shards > 0, "num_shards must be greater than zero");
+        (self.fingerprint() % num_shards as u64) as u32
+    }
+
+    /// Returns an 8-byte fingerprint of the identifier: the 64-bit FNV-1a hash
+    /// of its bytes
+    ///
+    /// The fingerprint is stable across processes, platforms and releases and
+    /// costs the same to compare whatever the identifier's length, which makes
+    /// it a fast deterministic tie-breaker. Distinct identifiers can collide,
+    /// so the raw bytes remain the source of truth for equality.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// assert_eq!(Identifier::from_bytes(vec![]).fingerprint(), 0xcbf2_9ce4_8422_2325);
+    /// assert_eq!(Identifier::from_name("a").fingerprint(), Identifier::from_name("a").fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = Fnv1aHasher::new();
+        hasher.write(&self.0);
+        hasher.finish()
+    }
+
+    /// Converts an identifier holding a GUID in the mixed-endian Microsoft
+    /// layout into canonical RFC 4122 byte order
+    ///
+    /// GUIDs store their first three fields little-endian, so the same UUID
+    /// received from a GUID-emitting system and from an RFC 4122 system has
+    /// different bytes and the two identifiers will not compare or hash equal.
+    /// Call this on identifiers known to come from GUID sources before using
+    /// them in clocks. The byte order cannot be detected from the bytes alone,
+    /// so calling it on an identifier that is already canonical scrambles it.
+    /// Identifiers that are not 16 bytes long are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Identifier;
+    ///
+    /// let guid = Identifier::from_bytes(vec![
+    ///     0x44, 0x50, 0xe5, 0x67, 0xb1, 0x10, 0x6f, 0x42,
+    ///     0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8,
+    /// ]);
+    /// assert_eq!(guid.canonical().to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    /// ```
+    pub fn canonical(&self) -> Identifier {
+        match <[u8; 16]>::try_from(self.0.as_slice()) {
+            Ok(bytes) => Identifier(Uuid::from_bytes_le(bytes).as_bytes().to_vec()),
+            Err(_) => self.clone(),
+        }
+    }
+}
+
+/// Formats 16-byte identifiers as a hyphenated UUID and any other length as
+/// standard base64
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_uuid() {
+            Some(uuid) => write!(f, "{}", uuid),
+            None => write!(f, "{}", general_purpose::STANDARD.encode(&self.0)),
         }
     }
 }
 
+/// Parses the output of `Display`: a hyphenated UUID becomes its 16 bytes and
+/// anything else is decoded as standard base64
+///
+/// Only the hyphenated UUID form is accepted, since standard base64 never
+/// contains `-` and the two branches therefore cannot be confused.
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::Identifier;
+///
+/// let id = Identifier::new();
+/// assert_eq!(id.to_string().parse::<Identifier>().unwrap(), id);
+/// ```
+impl FromStr for Identifier {
+    type Err = ClockError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 36 {
+            if let Ok(uuid) = Uuid::try_parse(s) {
+                return Ok(Identifier(uuid.as_bytes().to_vec()));
+            }
+        }
+        general_purpose::STANDARD
+            .decode(s)
+            .map(Identifier)
+            .map_err(|_| ClockError::InvalidIdentifier)
+    }
+}
+
+impl From<Uuid> for Identifier {
+    fn from(uuid: Uuid) -> Self {
+        Identifier(uuid.as_bytes().to_vec())
+    }
+}
+
+/// Stores the value as 16 big-endian bytes, so it formats as the UUID with the
+/// same numeric value
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::Identifier;
+///
+/// let id = Identifier::from(1u128);
+/// assert_eq!(id.to_string(), "00000000-0000-0000-0000-000000000001");
+/// ```
+impl From<u128> for Identifier {
+    fn from(value: u128) -> Self {
+        Identifier(value.to_be_bytes().to_vec())
+    }
+}
+
+/// Parses a UUID string in any format `Uuid` accepts; unlike `FromStr`, base64
+/// is rejected
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::Identifier;
+///
+/// let id = Identifier::try_from("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+/// assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+/// assert!(Identifier::try_from("not a uuid").is_err());
+/// ```
+impl TryFrom<&str> for Identifier {
+    type Error = ClockError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Uuid::try_parse(s)
+            .map(Identifier::from)
+            .map_err(|_| ClockError::InvalidIdentifier)
+    }
+}
+
+/// Fails with `ClockError::InvalidIdentifierLength` unless the identifier is
+/// exactly 16 bytes long
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::{ClockError, Identifier};
+/// use uuid::Uuid;
+///
+/// let uuid = Uuid::new_v4();
+/// assert_eq!(Uuid::try_from(&Identifier::from(uuid)).unwrap(), uuid);
+/// assert_eq!(
+///     Uuid::try_from(&Identifier::from_bytes(vec![1, 2, 3])),
+///     Err(ClockError::InvalidIdentifierLength(3))
+/// );
+/// ```
+impl TryFrom<&Identifier> for Uuid {
+    type Error = ClockError;
+
+    fn try_from(id: &Identifier) -> Result<Self, Self::Error> {
+        id.as_uuid().ok_or(ClockError::InvalidIdentifierLength(id.0.len()))
+    }
+}
+
+#[cfg(feature = "std")]
 impl Default for Identifier {
     fn default() -> Self {
         Identifier::new()
     }
 }
+
+/// Maps identifiers to human-assigned names and back, for observability tooling
+#[derive(Debug, Clone, Default)]
+pub struct IdentifierRegistry {
+    names: HashMap<Identifier, String>,
+    ids: HashMap<String, Identifier>,
+}
+
+impl IdentifierRegistry {
+    /// Creates a new empty registry
+    pub fn new() -> Self {
+        IdentifierRegistry::default()
+    }
+
+    /// Registers `name` for `id`, returning `false` if the name is already taken
+    /// by a different identifier. Re-registering an identifier replaces its
+    /// previous name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, IdentifierRegistry};
+    ///
+    /// let mut registry = IdentifierRegistry::new();
+    /// let id = Identifier::new();
+    /// assert!(registry.register(id.clone(), "node-a"));
+    /// assert_eq!(registry.name_of(&id), Some("node-a"));
+    /// assert_eq!(registry.id_of("node-a"), Some(&id));
+    /// ```
+    pub fn register(&mut self, id: Identifier, name: impl Into<String>) -> bool {
+        let name = name.into();
+        if let Some(owner) = self.ids.get(&name) {
+            return *owner == id;
+        }
+
+        if let Some(previous) = self.names.insert(id.clone(), name.clone()) {
+            self.ids.remove(&previous);
+        }
+        self.ids.insert(name, id);
+        true
+    }
+
+    /// Returns the name registered for `id`
+    pub fn name_of(&self, id: &Identifier) -> Option<&str> {
+        self.names.get(id).map(String::as_str)
+    }
+
+    /// Returns the identifier registered under `name`
+    pub fn id_of(&self, name: &str) -> Option<&Identifier> {
+        self.ids.get(name)
+    }
+}
+
+/// Assigns each distinct identifier a short stable label, `n0`, `n1`, ..., in
+/// sorted identifier order, for presenting clocks without revealing real node ids
+///
+/// The labels depend only on the set of identifiers, not on their order or
+/// duplicates in `ids`. Numbers are zero-padded to a common width (`n00` to
+/// `n11` for twelve identifiers), so the labels sort as strings in the same
+/// order as the identifiers. Use `deanonymize` to map labels back.
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::{anonymize, deanonymize, Identifier};
+///
+/// let ids = vec![Identifier::from_bytes(vec![2]), Identifier::from_bytes(vec![1])];
+/// let labels = anonymize(&ids);
+/// assert_eq!(labels[&ids[1]], "n0");
+/// assert_eq!(labels[&ids[0]], "n1");
+/// assert_eq!(deanonymize(&labels)["n1"], ids[0]);
+/// ```
+pub fn anonymize(ids: &[Identifier]) -> HashMap<Identifier, String> {
+    let mut sorted: Vec<_> = ids.iter().collect();
+    sorted.sort();
+    sorted.dedup();
+    let width = format!("{}", sorted.len().saturating_sub(1)).len();
+    sorted
+        .into_iter()
+        .enumerate()
+        .map(|(i, id)| (id.clone(), format!("n{:0width$}", i, width = width)))
+        .collect()
+}
+
+/// Inverts a labeling produced by `anonymize`
+pub fn deanonymize(labels: &HashMap<Identifier, String>) -> HashMap<String, Identifier> {
+    labels
+        .iter()
+        .map(|(id, label)| (label.clone(), id.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_registry_lookup() {
+        let mut registry = IdentifierRegistry::new();
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        assert!(registry.register(id1.clone(), "node-a"));
+        assert!(registry.register(id2.clone(), "node-b"));
+
+        assert_eq!(registry.name_of(&id1), Some("node-a"));
+        assert_eq!(registry.name_of(&id2), Some("node-b"));
+        assert_eq!(registry.id_of("node-a"), Some(&id1));
+        assert_eq!(registry.id_of("node-b"), Some(&id2));
+        assert_eq!(registry.id_of("node-c"), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_registry_duplicate_name() {
+        let mut registry = IdentifierRegistry::new();
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        assert!(registry.register(id1.clone(), "node-a"));
+
+        // The name already belongs to another identifier
+        assert!(!registry.register(id2.clone(), "node-a"));
+        assert_eq!(registry.id_of("node-a"), Some(&id1));
+        assert_eq!(registry.name_of(&id2), None);
+
+        // Renaming an identifier releases its old name
+        assert!(registry.register(id1.clone(), "node-z"));
+        assert_eq!(registry.id_of("node-a"), None);
+        assert_eq!(registry.name_of(&id1), Some("node-z"));
+    }
+
+    #[cfg(feature = "hashing")]
+    #[test]
+    fn test_from_content() {
+        let id = Identifier::from_content(b"public-key-a");
+
+        assert_eq!(id, Identifier::from_content(b"public-key-a"));
+        assert_ne!(id, Identifier::from_content(b"public-key-b"));
+        assert_eq!(id.0.len(), 32);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_generate() {
+        let ids = Identifier::generate(100);
+        let unique: HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), 100);
+    }
+
+    #[test]
+    fn test_generate_named() {
+        let ids = Identifier::generate_named("node", 10);
+        assert_eq!(ids, Identifier::generate_named("node", 10));
+        assert_eq!(ids[3], Identifier::from_name("node-3"));
+
+        let unique: HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), 10);
+
+        assert_ne!(ids, Identifier::generate_named("replica", 10));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_shard() {
+        let id = Identifier::from_bytes(vec![1, 2, 3, 4]);
+        assert_eq!(id.shard(7), id.shard(7));
+        assert_eq!(id.shard(7), Identifier::from_bytes(vec![1, 2, 3, 4]).shard(7));
+        assert_eq!(id.shard(1), 0);
+
+        // Identifiers spread evenly across shards
+        let mut counts = [0u32; 8];
+        for id in Identifier::generate(8000) {
+            counts[id.shard(8) as usize] += 1;
+        }
+        for count in counts {
+            assert!((800..1200).contains(&count), "uneven shard distribution: {:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_canonical_guid_order() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let canonical = Identifier::from_bytes(uuid.as_bytes().to_vec());
+
+        let guid = Identifier::from_bytes(vec![
+            0x44, 0x50, 0xe5, 0x67, 0xb1, 0x10, 0x6f, 0x42, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8,
+        ]);
+        assert_ne!(guid, canonical);
+        assert_eq!(guid.canonical(), canonical);
+
+        // Non-UUID identifiers are left untouched
+        let short = Identifier::from_bytes(vec![1, 2, 3]);
+        assert_eq!(short.canonical(), short);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_nil_identifier() {
+        assert_eq!(Identifier::nil(), Identifier::nil());
+        assert_eq!(Identifier::nil().0, vec![0u8; 16]);
+        assert_ne!(Identifier::nil(), Identifier::default());
+    }
+
+    #[test]
+    fn test_identifier_display() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let id = Identifier::from_bytes(uuid.as_bytes().to_vec());
+        assert_eq!(format!("{}", id), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+        assert_eq!(Identifier::from_bytes(vec![1, 2, 3]).to_string(), "AQID");
+    }
+
+    #[test]
+    fn test_identifier_from_urn() {
+        let id = Identifier::from_urn("URN:UUID:67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        assert_eq!(
+            id.0,
+            vec![
+                0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0,
+                0xc8
+            ]
+        );
+        assert_eq!(Identifier::from_urn(&format!("urn:uuid:{}", id)), Ok(id));
+
+        assert_eq!(
+            Identifier::from_urn("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            Err(ClockError::InvalidIdentifier)
+        );
+        assert_eq!(Identifier::from_urn("urn:uuid:nope"), Err(ClockError::InvalidIdentifier));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_identifier_as_uuid() {
+        let uuid = Uuid::new_v4();
+        let id = Identifier::from_bytes(uuid.as_bytes().to_vec());
+        assert_eq!(id.as_uuid(), Some(uuid));
+        assert_eq!(id.to_string(), uuid.to_string());
+
+        let short = Identifier::from_bytes(vec![0xff; 5]);
+        assert_eq!(short.as_uuid(), None);
+        assert_eq!(short.to_string(), general_purpose::STANDARD.encode([0xff; 5]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_identifier_from_str_round_trip() {
+        let uuid_id = Identifier::new();
+        assert_eq!(Identifier::from_str(&uuid_id.to_string()).unwrap(), uuid_id);
+
+        for bytes in [vec![], vec![1, 2, 3], vec![0xfb; 22], vec![0xab; 27]] {
+            let id = Identifier::from_bytes(bytes);
+            assert_eq!(Identifier::from_str(&id.to_string()).unwrap(), id);
+        }
+
+        assert_eq!(Identifier::from_str("not an id!"), Err(ClockError::InvalidIdentifier));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_anonymize_is_deterministic() {
+        let ids = Identifier::generate(5);
+        let labels = anonymize(&ids);
+
+        let mut shuffled = ids.clone();
+        shuffled.reverse();
+        shuffled.push(ids[2].clone());
+        assert_eq!(anonymize(&shuffled), labels);
+
+        let mut sorted = ids.clone();
+        sorted.sort();
+        for (i, id) in sorted.iter().enumerate() {
+            assert_eq!(labels[id], format!("n{}", i));
+        }
+
+        let inverse = deanonymize(&labels);
+        assert_eq!(inverse.len(), ids.len());
+        for id in &ids {
+            assert_eq!(&inverse[&labels[id]], id);
+        }
+    }
+
+    #[test]
+    fn test_anonymize_labels_sort_like_identifiers() {
+        let ids: Vec<Identifier> = (0..12u8).rev().map(|i| Identifier::from_bytes(vec![i])).collect();
+        let labels = anonymize(&ids);
+        assert_eq!(labels[&Identifier::from_bytes(vec![0])], "n00");
+        assert_eq!(labels[&Identifier::from_bytes(vec![11])], "n11");
+
+        let mut sorted = ids.clone();
+        sorted.sort();
+        let mut by_label = ids.clone();
+        by_label.sort_by(|a, b| labels[a].cmp(&labels[b]));
+        assert_eq!(by_label, sorted);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_identifier_conversions() {
+        let uuid = Uuid::new_v4();
+        let id = Identifier::from(uuid);
+        assert_eq!(id.as_uuid(), Some(uuid));
+        assert_eq!(Uuid::try_from(&id), Ok(uuid));
+
+        let value = 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10u128;
+        let id = Identifier::from(value);
+        assert_eq!(id.0, (1..=16).collect::<Vec<u8>>());
+        assert_eq!(Uuid::try_from(&id).unwrap().as_u128(), value);
+
+        let parsed = Identifier::try_from(uuid.to_string().as_str()).unwrap();
+        assert_eq!(parsed, Identifier::from(uuid));
+        assert_eq!(Identifier::try_from(""), Err(ClockError::InvalidIdentifier));
+        assert_eq!(
+            Uuid::try_from(&Identifier::from_bytes(vec![0; 20])),
+            Err(ClockError::InvalidIdentifierLength(20))
+        );
+    }
+}
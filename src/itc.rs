@@ -0,0 +1,500 @@
+use alloc::boxed::Box;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Cost added by `grow` when it has to expand an event leaf into a node, large
+/// enough that growing into an existing subtree is always preferred
+const EXPAND_COST: u64 = 1 << 32;
+
+/// The id component of a stamp: the part of the interval `[0, 1)` a stamp owns
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum IdTree {
+    Zero,
+    One,
+    Node(Box<IdTree>, Box<IdTree>),
+}
+
+impl IdTree {
+    fn node(left: IdTree, right: IdTree) -> IdTree {
+        match (left, right) {
+            (IdTree::Zero, IdTree::Zero) => IdTree::Zero,
+            (IdTree::One, IdTree::One) => IdTree::One,
+            (left, right) => IdTree::Node(Box::new(left), Box::new(right)),
+        }
+    }
+
+    /// Splits the owned interval into two disjoint halves
+    fn split(&self) -> (IdTree, IdTree) {
+        match self {
+            IdTree::Zero => (IdTree::Zero, IdTree::Zero),
+            IdTree::One => (
+                IdTree::node(IdTree::One, IdTree::Zero),
+                IdTree::node(IdTree::Zero, IdTree::One),
+            ),
+            IdTree::Node(left, right) => match (left.as_ref(), right.as_ref()) {
+                (IdTree::Zero, right) => {
+                    let (a, b) = right.split();
+                    (IdTree::node(IdTree::Zero, a), IdTree::node(IdTree::Zero, b))
+                }
+                (left, IdTree::Zero) => {
+                    let (a, b) = left.split();
+                    (IdTree::node(a, IdTree::Zero), IdTree::node(b, IdTree::Zero))
+                }
+                (left, right) => (
+                    IdTree::node(left.clone(), IdTree::Zero),
+                    IdTree::node(IdTree::Zero, right.clone()),
+                ),
+            },
+        }
+    }
+
+    /// Merges two ids. Ids of live stamps are disjoint; overlapping ids are
+    /// merged as a union.
+    fn sum(self, other: IdTree) -> IdTree {
+        match (self, other) {
+            (IdTree::Zero, id) | (id, IdTree::Zero) => id,
+            (IdTree::One, _) | (_, IdTree::One) => IdTree::One,
+            (IdTree::Node(l1, r1), IdTree::Node(l2, r2)) => IdTree::node(l1.sum(*l2), r1.sum(*r2)),
+        }
+    }
+}
+
+impl fmt::Display for IdTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdTree::Zero => write!(f, "0"),
+            IdTree::One => write!(f, "1"),
+            IdTree::Node(left, right) => write!(f, "({}, {})", left, right),
+        }
+    }
+}
+
+/// The event component of a stamp: a tree of counters over `[0, 1)`, where a
+/// node's value is added to everything below it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum EventTree {
+    Leaf(u64),
+    Node(u64, Box<EventTree>, Box<EventTree>),
+}
+
+impl EventTree {
+    /// Builds a node in normal form: equal leaf children collapse into the
+    /// parent and the children's common minimum is lifted into the parent
+    fn node(n: u64, left: EventTree, right: EventTree) -> EventTree {
+        match (left, right) {
+            (EventTree::Leaf(a), EventTree::Leaf(b)) if a == b => EventTree::Leaf(n + a),
+            (left, right) => {
+                let m = left.min().min(right.min());
+                EventTree::Node(n + m, Box::new(left.sink(m)), Box::new(right.sink(m)))
+            }
+        }
+    }
+
+    fn base(&self) -> u64 {
+        match self {
+            EventTree::Leaf(n) | EventTree::Node(n, _, _) => *n,
+        }
+    }
+
+    fn min(&self) -> u64 {
+        match self {
+            EventTree::Leaf(n) => *n,
+            EventTree::Node(n, left, right) => n + left.min().min(right.min()),
+        }
+    }
+
+    fn max(&self) -> u64 {
+        match self {
+            EventTree::Leaf(n) => *n,
+            EventTree::Node(n, left, right) => n + left.max().max(right.max()),
+        }
+    }
+
+    fn lift(self, m: u64) -> EventTree {
+        match self {
+            EventTree::Leaf(n) => EventTree::Leaf(n + m),
+            EventTree::Node(n, left, right) => EventTree::Node(n + m, left, right),
+        }
+    }
+
+    fn sink(self, m: u64) -> EventTree {
+        match self {
+            EventTree::Leaf(n) => EventTree::Leaf(n - m),
+            EventTree::Node(n, left, right) => EventTree::Node(n - m, left, right),
+        }
+    }
+
+    /// Splits the tree into its base value and its children, treating a leaf
+    /// as a node with zero children
+    fn unfold(&self) -> (u64, EventTree, EventTree) {
+        match self {
+            EventTree::Leaf(n) => (*n, EventTree::Leaf(0), EventTree::Leaf(0)),
+            EventTree::Node(n, left, right) => (*n, left.as_ref().clone(), right.as_ref().clone()),
+        }
+    }
+
+    fn leq(&self, other: &EventTree) -> bool {
+        match (self, other) {
+            (EventTree::Leaf(n1), _) => *n1 <= other.base(),
+            (EventTree::Node(n1, l1, r1), EventTree::Leaf(n2)) => {
+                n1 <= n2
+                    && l1.as_ref().clone().lift(*n1).leq(other)
+                    && r1.as_ref().clone().lift(*n1).leq(other)
+            }
+            (EventTree::Node(n1, l1, r1), EventTree::Node(n2, l2, r2)) => {
+                n1 <= n2
+                    && l1.as_ref().clone().lift(*n1).leq(&l2.as_ref().clone().lift(*n2))
+                    && r1.as_ref().clone().lift(*n1).leq(&r2.as_ref().clone().lift(*n2))
+            }
+        }
+    }
+
+    fn join(&self, other: &EventTree) -> EventTree {
+        match (self, other) {
+            (EventTree::Leaf(n1), EventTree::Leaf(n2)) => EventTree::Leaf(*n1.max(n2)),
+            _ if self.base() > other.base() => other.join(self),
+            _ => {
+                let (n1, l1, r1) = self.unfold();
+                let (n2, l2, r2) = other.unfold();
+                let d = n2 - n1;
+                EventTree::node(n1, l1.join(&l2.lift(d)), r1.join(&r2.lift(d)))
+            }
+        }
+    }
+
+    /// Raises, without adding any new event, every part of the tree owned by
+    /// `id` as far as the tree already allows
+    fn fill(&self, id: &IdTree) -> EventTree {
+        match (id, self) {
+            (IdTree::Zero, _) => self.clone(),
+            (IdTree::One, _) => EventTree::Leaf(self.max()),
+            (_, EventTree::Leaf(_)) => self.clone(),
+            (IdTree::Node(il, ir), EventTree::Node(n, el, er)) => match (il.as_ref(), ir.as_ref()) {
+                (IdTree::One, ir) => {
+                    let er = er.fill(ir);
+                    let left = EventTree::Leaf(el.max().max(er.min()));
+                    EventTree::node(*n, left, er)
+                }
+                (il, IdTree::One) => {
+                    let el = el.fill(il);
+                    let right = EventTree::Leaf(er.max().max(el.min()));
+                    EventTree::node(*n, el, right)
+                }
+                (il, ir) => EventTree::node(*n, el.fill(il), er.fill(ir)),
+            },
+        }
+    }
+
+    /// Inflates the tree by one event in a part owned by `id`, choosing the
+    /// growth that keeps the tree smallest; returns the new tree and its cost
+    fn grow(&self, id: &IdTree) -> (EventTree, u64) {
+        match (id, self) {
+            (IdTree::One, EventTree::Leaf(n)) => (EventTree::Leaf(n + 1), 0),
+            (_, EventTree::Leaf(n)) => {
+                let expanded = EventTree::Node(
+                    *n,
+                    Box::new(EventTree::Leaf(0)),
+                    Box::new(EventTree::Leaf(0)),
+                );
+                let (tree, cost) = expanded.grow(id);
+                (tree, cost + EXPAND_COST)
+            }
+            (IdTree::Node(il, ir), EventTree::Node(n, el, er)) => match (il.as_ref(), ir.as_ref()) {
+                (IdTree::Zero, ir) => {
+                    let (er, cost) = er.grow(ir);
+                    (EventTree::Node(*n, el.clone(), Box::new(er)), cost + 1)
+                }
+                (il, IdTree::Zero) => {
+                    let (el, cost) = el.grow(il);
+                    (EventTree::Node(*n, Box::new(el), er.clone()), cost + 1)
+                }
+                (il, ir) => {
+                    let (grown_left, cost_left) = el.grow(il);
+                    let (grown_right, cost_right) = er.grow(ir);
+                    if cost_left < cost_right {
+                        (EventTree::Node(*n, Box::new(grown_left), er.clone()), cost_left + 1)
+                    } else {
+                        (EventTree::Node(*n, el.clone(), Box::new(grown_right)), cost_right + 1)
+                    }
+                }
+            },
+            // A normalized node always differs from its maximum, so `fill`
+            // handles a whole id before `grow` is reached, and anonymous
+            // stamps are rejected by `Stamp::event`
+            (IdTree::One, EventTree::Node(..)) | (IdTree::Zero, EventTree::Node(..)) => {
+                unreachable!("grow is only called on non-anonymous ids that fill could not advance")
+            }
+        }
+    }
+}
+
+impl fmt::Display for EventTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventTree::Leaf(n) => write!(f, "{}", n),
+            EventTree::Node(n, left, right) => write!(f, "({}, {}, {})", n, left, right),
+        }
+    }
+}
+
+/// An Interval Tree Clock stamp (Almeida, Baquero and Fonte, 2008)
+///
+/// Unlike vector clocks, ITC needs no registry of node identifiers: a system
+/// starts from a single `seed` stamp, and nodes appear by `fork`ing an
+/// existing stamp and disappear by `join`ing theirs into another one. Each
+/// stamp owns a disjoint part of an interval (its id) and records the events
+/// it knows about as a tree over that interval, so the stamp size adapts to
+/// the number of live participants rather than every node ever seen.
+///
+/// Stamps are compared with `leq`, which is the causal order: `a.leq(&b)`
+/// means every event known to `a` is known to `b`. `Display` uses the
+/// paper's notation, `(id, event)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stamp {
+    id: IdTree,
+    event: EventTree,
+}
+
+impl Stamp {
+    /// Returns the seed stamp, which owns the whole interval and has seen no
+    /// events
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Stamp;
+    ///
+    /// assert_eq!(Stamp::seed().to_string(), "(1, 0)");
+    /// ```
+    pub fn seed() -> Self {
+        Stamp {
+            id: IdTree::One,
+            event: EventTree::Leaf(0),
+        }
+    }
+
+    /// Splits this stamp into two stamps with disjoint ids and the same
+    /// causal history, e.g. to hand one to a newly created node
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Stamp;
+    ///
+    /// let (a, b) = Stamp::seed().fork();
+    /// assert_eq!(a.to_string(), "((1, 0), 0)");
+    /// assert_eq!(b.to_string(), "((0, 1), 0)");
+    /// ```
+    pub fn fork(self) -> (Stamp, Stamp) {
+        let (left, right) = self.id.split();
+        (
+            Stamp {
+                id: left,
+                event: self.event.clone(),
+            },
+            Stamp {
+                id: right,
+                event: self.event,
+            },
+        )
+    }
+
+    /// Records a new event, inflating the event tree within the part of the
+    /// interval this stamp owns
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stamp is anonymous, i.e. owns no part of the interval,
+    /// like the stamps returned by `peek`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Stamp;
+    ///
+    /// let (mut a, b) = Stamp::seed().fork();
+    /// a.event();
+    /// assert!(b.leq(&a));
+    /// assert!(!a.leq(&b));
+    /// ```
+    pub fn event(&mut self) {
+        assert!(!self.is_anonymous(), "cannot record an event on an anonymous stamp");
+        let filled = self.event.fill(&self.id);
+        self.event = if filled != self.event {
+            filled
+        } else {
+            self.event.grow(&self.id).0
+        };
+    }
+
+    /// Merges two stamps into one that owns both ids and knows both causal
+    /// histories, e.g. when a node retires or a message is received
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::Stamp;
+    ///
+    /// let (mut a, mut b) = Stamp::seed().fork();
+    /// a.event();
+    /// b.event();
+    /// assert_eq!(a.join(b).to_string(), "(1, 1)");
+    /// ```
+    pub fn join(self, other: Stamp) -> Stamp {
+        Stamp {
+            id: self.id.sum(other.id),
+            event: self.event.join(&other.event),
+        }
+    }
+
+    /// Returns an anonymous copy of this stamp that carries its causal history
+    /// but owns no part of the interval, for attaching to outgoing messages
+    pub fn peek(&self) -> Stamp {
+        Stamp {
+            id: IdTree::Zero,
+            event: self.event.clone(),
+        }
+    }
+
+    /// Checks whether this stamp owns no part of the interval
+    pub fn is_anonymous(&self) -> bool {
+        self.id == IdTree::Zero
+    }
+
+    /// Checks whether every event known to this stamp is known to `other`
+    pub fn leq(&self, other: &Stamp) -> bool {
+        self.event.leq(&other.event)
+    }
+
+    /// Checks whether neither stamp knows every event of the other
+    pub fn concurrent(&self, other: &Stamp) -> bool {
+        !self.leq(other) && !other.leq(self)
+    }
+}
+
+impl Default for Stamp {
+    fn default() -> Self {
+        Stamp::seed()
+    }
+}
+
+impl fmt::Display for Stamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.id, self.event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_itc_paper_example() {
+        // Starts with the fork/event/join run the ITC paper walks through,
+        // checked in the paper's notation
+        let seed = Stamp::seed();
+        assert_eq!(seed.to_string(), "(1, 0)");
+
+        let (mut a, mut b) = seed.fork();
+        assert_eq!(a.to_string(), "((1, 0), 0)");
+        assert_eq!(b.to_string(), "((0, 1), 0)");
+
+        a.event();
+        b.event();
+        assert_eq!(a.to_string(), "((1, 0), (0, 1, 0))");
+        assert_eq!(b.to_string(), "((0, 1), (0, 0, 1))");
+
+        let (mut a1, a2) = a.fork();
+        b.event();
+        assert_eq!(a1.to_string(), "(((1, 0), 0), (0, 1, 0))");
+        assert_eq!(a2.to_string(), "(((0, 1), 0), (0, 1, 0))");
+        assert_eq!(b.to_string(), "((0, 1), (0, 0, 2))");
+
+        a1.event();
+        let mut c = a2.join(b);
+        assert_eq!(a1.to_string(), "(((1, 0), 0), (0, (1, 1, 0), 0))");
+        assert_eq!(c.to_string(), "(((0, 1), 1), (1, 0, 1))");
+
+        let (c1, c2) = c.clone().fork();
+        assert_eq!(c1.to_string(), "(((0, 1), 0), (1, 0, 1))");
+        assert_eq!(c2.to_string(), "((0, 1), (1, 0, 1))");
+
+        let mut d = a1.join(c1);
+        d.event();
+        assert_eq!(d.to_string(), "((1, 0), 2)");
+        assert!(c2.leq(&d));
+        assert!(!d.leq(&c2));
+
+        c.event();
+        assert!(c.concurrent(&d));
+        assert_eq!(d.join(c).to_string(), "(1, (2, 0, 1))");
+    }
+
+    #[test]
+    fn test_itc_join_restores_seed_id() {
+        let (a, b) = Stamp::seed().fork();
+        let (b, c) = b.fork();
+        let (c, d) = c.fork();
+        let mut stamps = [a, b, c, d];
+        for (i, stamp) in stamps.iter_mut().enumerate() {
+            for _ in 0..=i {
+                stamp.event();
+            }
+        }
+
+        let joined = stamps
+            .iter()
+            .cloned()
+            .reduce(|acc, stamp| acc.join(stamp))
+            .unwrap();
+        assert!(stamps.iter().all(|stamp| stamp.leq(&joined)));
+        assert_eq!(joined.id, IdTree::One);
+        for (i, a) in stamps.iter().enumerate() {
+            for b in &stamps[i + 1..] {
+                assert!(a.concurrent(b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_itc_peek_and_anonymous_stamps() {
+        let (mut a, mut b) = Stamp::seed().fork();
+        a.event();
+        let message = a.peek();
+        assert!(message.is_anonymous());
+        assert!(message.leq(&a) && a.leq(&message));
+
+        // Receiving a message joins its history without taking over any id
+        b = b.join(message);
+        assert!(a.leq(&b));
+        b.event();
+        assert!(a.leq(&b) && !b.leq(&a));
+        assert_eq!(b.id, IdTree::node(IdTree::Zero, IdTree::One));
+    }
+
+    #[test]
+    #[should_panic(expected = "anonymous stamp")]
+    fn test_itc_event_on_anonymous_stamp_panics() {
+        Stamp::seed().peek().event();
+    }
+
+    #[test]
+    fn test_itc_events_are_monotonic() {
+        let (mut a, b) = Stamp::seed().fork();
+        let (mut b, mut c) = b.fork();
+        for round in 0..20 {
+            let before = (a.clone(), b.clone(), c.clone());
+            match round % 3 {
+                0 => a.event(),
+                1 => b.event(),
+                _ => {
+                    c = c.join(a.peek());
+                    c.event();
+                }
+            }
+            assert!(before.0.leq(&a) && before.1.leq(&b) && before.2.leq(&c));
+            assert!(!a.leq(&before.0) || !b.leq(&before.1) || !c.leq(&before.2));
+        }
+    }
+}
@@ -1,13 +1,150 @@
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::cmp::Ordering as CmpOrdering;
-use std::convert::TryInto;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::cmp::Ordering as CmpOrdering;
+use core::convert::TryInto;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::sync::mpsc::Receiver;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
 use serde::{Serialize, Deserialize};
-use crate::Identifier;
+#[cfg(feature = "std")]
+use bincode::{Error as BincodeError, Options};
+#[cfg(feature = "std")]
+use crate::codec::bincode_options;
+use crate::codec::{read_identifier, read_u32, read_u64, write_identifier};
+use crate::compat::HashMap;
+use crate::{ClockError, Identifier};
 
 /// Represents a Lamport time value.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct LamportTime(pub u64, pub Identifier);
 
+impl LamportTime {
+    /// Serializes the time to bytes.
+    ///
+    /// The format is bincode with a pinned configuration: the little-endian `u64` counter
+    /// followed by the identifier as a little-endian `u64` length and its bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportTime};
+    ///
+    /// let time = LamportTime(42, Identifier::new());
+    /// let bytes = time.to_bytes().unwrap();
+    /// assert_eq!(LamportTime::from_bytes(&bytes).unwrap(), time);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BincodeError> {
+        bincode_options().serialize(self)
+    }
+
+    /// Deserializes the time from bytes produced by `to_bytes`.
+    #[cfg(feature = "std")]
+    pub fn from_bytes(data: &[u8]) -> Result<Self, BincodeError> {
+        bincode_options().deserialize(data)
+    }
+
+    /// Encodes the time as a string whose lexicographic order matches the
+    /// `Ord` implementation, for storage in text-sorted database columns.
+    ///
+    /// The counter is written as 20 zero-padded decimal digits, followed by a
+    /// `-` and the identifier bytes in lowercase hex.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportTime};
+    ///
+    /// let time = LamportTime(42, Identifier::from_bytes(vec![0xab, 0x01]));
+    /// assert_eq!(time.to_sortable_string(), "00000000000000000042-ab01");
+    /// ```
+    pub fn to_sortable_string(&self) -> String {
+        let mut encoded = format!("{:020}-", self.0);
+        for byte in &self.1.0 {
+            encoded.push_str(&format!("{:02x}", byte));
+        }
+        encoded
+    }
+
+    /// Decodes a time produced by `to_sortable_string`.
+    pub fn from_sortable_string(s: &str) -> Option<Self> {
+        let (counter, id) = s.split_once('-')?;
+        if counter.len() != 20 || id.len() % 2 != 0 || !counter.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let counter = counter.parse().ok()?;
+        let bytes = (0..id.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(id.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        Some(LamportTime(counter, Identifier(bytes)))
+    }
+
+    /// Packs the time into a single `u128` key for sorted indexes, with the
+    /// counter in the high 64 bits and the first 8 bytes of the identifier,
+    /// zero-padded, in the low 64 bits.
+    ///
+    /// The identifier prefix orders like the identifier itself, so the key
+    /// never contradicts `LamportTime::cmp`: a smaller time never gets a larger
+    /// key. Identifiers that share their first 8 bytes (or differ only in
+    /// trailing zero bytes) collide on the same key for equal counters, so keep
+    /// the full time next to the key when the tie-break matters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportTime};
+    ///
+    /// let id = Identifier::new();
+    /// let earlier = LamportTime(1, id.clone()).to_u128_key();
+    /// let later = LamportTime(2, id).to_u128_key();
+    /// assert!(earlier < later);
+    /// ```
+    pub fn to_u128_key(&self) -> u128 {
+        let mut prefix = [0u8; 8];
+        let len = self.1.0.len().min(prefix.len());
+        prefix[..len].copy_from_slice(&self.1.0[..len]);
+        ((self.0 as u128) << 64) | u64::from_be_bytes(prefix) as u128
+    }
+
+    /// Serializes the time to a JSON object `{"counter": ..., "id": ...}`, with the identifier in
+    /// its string form (UUID or base64).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportTime};
+    ///
+    /// let time = LamportTime(42, Identifier::nil());
+    /// let json = time.to_json();
+    /// assert_eq!(json, r#"{"counter":42,"id":"00000000-0000-0000-0000-000000000000"}"#);
+    /// assert_eq!(LamportTime::from_json(&json).unwrap(), time);
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::json!({ "counter": self.0, "id": self.1.to_string() }).to_string()
+    }
+
+    /// Deserializes the time from the JSON produced by `to_json`.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, ClockError> {
+        #[derive(Deserialize)]
+        struct Json {
+            counter: u64,
+            id: String,
+        }
+
+        let parsed: Json =
+            serde_json::from_str(json).map_err(|err| ClockError::Serialization(err.to_string()))?;
+        Ok(LamportTime(parsed.counter, parsed.id.parse()?))
+    }
+}
+
 impl PartialOrd for LamportTime {
     fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
         Some(self.cmp(other))
@@ -20,14 +157,190 @@ impl Ord for LamportTime {
     }
 }
 
+/// Formats the time as `<counter>@<identifier>`, e.g. `42@67e55044-10b1-426f-9247-bb680e5fe0c8`.
+impl fmt::Display for LamportTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.0, self.1)
+    }
+}
+
+/// A Lamport time annotated with the wall-clock time it was generated at, for
+/// human-readable logs.
+///
+/// The wall-clock time is informational only: equality and ordering use just the
+/// `LamportTime`, so clock skew between machines never affects causal order.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampedLamportTime {
+    /// The Lamport time, which alone determines ordering.
+    pub time: LamportTime,
+    /// The wall-clock time at which the stamp was generated.
+    pub wall: SystemTime,
+}
+
+#[cfg(feature = "std")]
+impl PartialEq for TimestampedLamportTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.time == other.time
+    }
+}
+
+#[cfg(feature = "std")]
+impl Eq for TimestampedLamportTime {}
+
+#[cfg(feature = "std")]
+impl PartialOrd for TimestampedLamportTime {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Ord for TimestampedLamportTime {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.time.cmp(&other.time)
+    }
+}
+
+/// A user payload tagged with the Lamport time at which it was recorded, forming an entry of
+/// a causal event log.
+///
+/// Events order by their `LamportTime`, whose `(counter, identifier)` order is total, so events
+/// with equal counters from different nodes still sort deterministically.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StampedEvent<T> {
+    /// The Lamport time at which the event was recorded.
+    pub time: LamportTime,
+    /// The user payload, e.g. an event name or request id.
+    pub payload: T,
+}
+
+impl<T> StampedEvent<T> {
+    /// Increments `clock` and tags `payload` with the new time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{LamportClock, StampedEvent};
+    ///
+    /// let clock = LamportClock::new();
+    /// let event = StampedEvent::record(&clock, "request-17");
+    /// assert_eq!(event.time, clock.time());
+    /// assert_eq!(event.payload, "request-17");
+    /// ```
+    pub fn record(clock: &LamportClock, payload: T) -> Self {
+        StampedEvent { time: clock.increment(), payload }
+    }
+
+    /// Sorts events by their Lamport times. The sort is stable, so events with identical times
+    /// keep their relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportTime, StampedEvent};
+    ///
+    /// let id = Identifier::new();
+    /// let mut log = vec![
+    ///     StampedEvent { time: LamportTime(2, id.clone()), payload: "second" },
+    ///     StampedEvent { time: LamportTime(1, id), payload: "first" },
+    /// ];
+    /// StampedEvent::sort_by_time(&mut log);
+    /// assert_eq!(log[0].payload, "first");
+    /// ```
+    pub fn sort_by_time(events: &mut [StampedEvent<T>]) {
+        events.sort_by(|a, b| a.time.cmp(&b.time));
+    }
+}
+
+/// Orders Lamport times by counter, breaking ties with a caller-assigned node
+/// rank instead of the identifier bytes.
+///
+/// Lower ranks sort first. Identifiers without a rank sort after every ranked
+/// one and fall back to the default identifier ordering among themselves, or to
+/// their fingerprints first for an ordering built with `by_fingerprint`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use logical_clocks_rs::{Identifier, LamportTime, LamportTimeOrd};
+///
+/// let leader = Identifier::new();
+/// let follower = Identifier::new();
+/// let ord = LamportTimeOrd::with_priorities(HashMap::from([
+///     (leader.clone(), 0),
+///     (follower.clone(), 1),
+/// ]));
+///
+/// let mut times = vec![LamportTime(3, follower), LamportTime(3, leader.clone())];
+/// ord.sort(&mut times);
+/// assert_eq!(times[0].1, leader);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LamportTimeOrd {
+    priorities: HashMap<Identifier, u64>,
+    by_fingerprint: bool,
+}
+
+impl LamportTimeOrd {
+    /// Creates an ordering that ranks identifiers by the given priorities.
+    pub fn with_priorities(priorities: HashMap<Identifier, u64>) -> Self {
+        LamportTimeOrd { priorities, by_fingerprint: false }
+    }
+
+    /// Creates an ordering that breaks counter ties by `Identifier::fingerprint` instead of the
+    /// identifier bytes, so tie-breaking costs the same for identifiers of any length. Times
+    /// whose fingerprints collide fall back to the identifier bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportTime, LamportTimeOrd};
+    ///
+    /// let (a, b) = (Identifier::from_name("a"), Identifier::from_name("b"));
+    /// let ord = LamportTimeOrd::by_fingerprint();
+    ///
+    /// let mut times = vec![LamportTime(1, a.clone()), LamportTime(1, b.clone())];
+    /// ord.sort(&mut times);
+    /// assert!(times[0].1.fingerprint() < times[1].1.fingerprint());
+    /// ```
+    pub fn by_fingerprint() -> Self {
+        LamportTimeOrd { priorities: HashMap::new(), by_fingerprint: true }
+    }
+
+    /// Compares two Lamport times, using node ranks to break counter ties.
+    pub fn cmp(&self, a: &LamportTime, b: &LamportTime) -> CmpOrdering {
+        a.0.cmp(&b.0)
+            .then_with(|| match (self.priorities.get(&a.1), self.priorities.get(&b.1)) {
+                (Some(pa), Some(pb)) => pa.cmp(pb),
+                (Some(_), None) => CmpOrdering::Less,
+                (None, Some(_)) => CmpOrdering::Greater,
+                (None, None) => CmpOrdering::Equal,
+            })
+            .then_with(|| match self.by_fingerprint {
+                true => a.1.fingerprint().cmp(&b.1.fingerprint()),
+                false => CmpOrdering::Equal,
+            })
+            .then_with(|| a.1.cmp(&b.1))
+    }
+
+    /// Sorts Lamport times in place under this ordering.
+    pub fn sort(&self, times: &mut [LamportTime]) {
+        times.sort_by(|a, b| self.cmp(a, b));
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LamportClock {
+    #[serde(with = "crate::codec::atomic_u64")]
     counter: AtomicU64,
     id: Identifier,
 }
 
 impl LamportClock {
     /// Creates a new Lamport clock with the counter initialized to 1.
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
         LamportClock {
             counter: AtomicU64::new(1),
@@ -76,9 +389,31 @@ impl LamportClock {
         LamportTime(self.counter.load(Ordering::SeqCst), self.id.clone())
     }
 
+    /// The `peek` method returns the current counter without reading or cloning the identifier,
+    /// for cheap high-frequency logging and metrics. It never advances the clock and is the
+    /// same read as `load_counter`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// clock.increment();
+    /// assert_eq!(clock.peek(), 2);
+    /// assert_eq!(clock.peek(), clock.time().0);
+    /// ```
+    ///
+    pub fn peek(&self) -> u64 {
+        self.load_counter()
+    }
+
      /// The `increment` method increments the Lamport clock and returns the new value.
     /// This method is typically used to record an event in the process and update the clock value.
     ///
+    /// The counter wraps from `u64::MAX` back to 0, which would corrupt causal order; use
+    /// `checked_increment` where a clock could conceivably reach the maximum.
+    ///
     /// # Examples
     ///
     /// ```
@@ -95,6 +430,154 @@ impl LamportClock {
        LamportTime(old_value + 1, self.id.clone())
     }
 
+    /// The `decrement` method atomically subtracts one from the counter to undo a speculatively
+    /// recorded event, e.g. when a transaction aborts before its message is sent. Returns the
+    /// new time, or `None` if the counter is already at its initial value of 1.
+    ///
+    /// Decrementing breaks the monotonicity guarantee of the clock. Only use it for local
+    /// speculative rollback, before any `time()` or `increment()` value covering the undone
+    /// event has been observed outside this process.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// clock.increment();
+    /// assert_eq!(clock.decrement().map(|time| time.0), Some(1));
+    /// assert_eq!(clock.decrement(), None);
+    /// ```
+    ///
+    pub fn decrement(&self) -> Option<LamportTime> {
+        loop {
+            let current = self.counter.load(Ordering::SeqCst);
+            if current <= 1 {
+                return None;
+            }
+
+            match self.counter.compare_exchange(current, current - 1, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return Some(LamportTime(current - 1, self.id.clone())),
+                Err(_) => continue,
+            }
+        }
+    }
+
+    /// The `checked_increment` method increments the Lamport clock like `increment`, but returns
+    /// `None` and leaves the clock untouched instead of wrapping when the counter is already at
+    /// `u64::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// assert_eq!(clock.checked_increment().map(|time| time.0), Some(2));
+    /// clock.store_counter(u64::MAX);
+    /// assert_eq!(clock.checked_increment(), None);
+    /// ```
+    ///
+    pub fn checked_increment(&self) -> Option<LamportTime> {
+        loop {
+            let current = self.counter.load(Ordering::SeqCst);
+            let new_value = current.checked_add(1)?;
+
+            match self.counter.compare_exchange(current, new_value, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return Some(LamportTime(new_value, self.id.clone())),
+                Err(_) => continue,
+            }
+        }
+    }
+
+    /// The `stamp_header` method increments the clock and returns the new time encoded with
+    /// `LamportTime::to_bytes`, ready to be written into an outgoing message header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{LamportClock, LamportTime};
+    ///
+    /// let clock = LamportClock::new();
+    /// let header = clock.stamp_header();
+    /// assert_eq!(LamportTime::from_bytes(&header).unwrap(), clock.time());
+    /// ```
+    ///
+    #[cfg(feature = "std")]
+    pub fn stamp_header(&self) -> Vec<u8> {
+        self.increment()
+            .to_bytes()
+            .expect("serializing a LamportTime into memory cannot fail")
+    }
+
+    /// The `increment_timestamped` method increments the clock like `increment` and annotates
+    /// the new time with the current wall-clock time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// let stamp = clock.increment_timestamped();
+    /// assert_eq!(stamp.time, clock.time());
+    /// ```
+    ///
+    #[cfg(feature = "std")]
+    pub fn increment_timestamped(&self) -> TimestampedLamportTime {
+        TimestampedLamportTime {
+            time: self.increment(),
+            wall: SystemTime::now(),
+        }
+    }
+
+    /// The `send` method records a send event: it increments the clock and returns the stamp
+    /// to attach to the outgoing message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let sender = LamportClock::new();
+    /// let receiver = LamportClock::new();
+    ///
+    /// let stamp = sender.send();
+    /// assert!(receiver.receive(&stamp).0 > stamp.0);
+    /// ```
+    ///
+    pub fn send(&self) -> LamportTime {
+        self.increment()
+    }
+
+    /// The `receive` method records a receive event: it atomically sets the counter to one past
+    /// the larger of the local counter and the message's counter, and returns the new local
+    /// time. The returned time is therefore always later than both the message and every
+    /// earlier local event, except at `u64::MAX`, where the counter saturates instead of
+    /// overflowing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportClock, LamportTime};
+    ///
+    /// let clock = LamportClock::new();
+    /// assert_eq!(clock.receive(&LamportTime(10, Identifier::nil())).0, 11);
+    /// assert_eq!(clock.receive(&LamportTime(3, Identifier::nil())).0, 12);
+    /// ```
+    ///
+    pub fn receive(&self, msg_time: &LamportTime) -> LamportTime {
+        loop {
+            let current = self.counter.load(Ordering::SeqCst);
+            let new_value = current.max(msg_time.0).saturating_add(1);
+
+            match self.counter.compare_exchange(current, new_value, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return LamportTime(new_value, self.id.clone()),
+                Err(_) => continue,
+            }
+        }
+    }
+
     /// The `compare` method updates the local clock if necessary after witnessing a clock value
     /// from another process. This ensures the Lamport clock maintains a consistent logical order
     /// of events in a distributed system.
@@ -111,20 +594,136 @@ impl LamportClock {
     /// ```
     ///
     pub fn compare(&self, other_time: LamportTime) {
+        self.witness(&other_time);
+    }
+
+    /// The `witness` method performs the same update as `compare` and returns the resulting
+    /// local time, read from inside the update loop rather than with a separate `time` call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportClock, LamportTime};
+    ///
+    /// let clock = LamportClock::new();
+    /// assert_eq!(clock.witness(&LamportTime(10, Identifier::nil())).0, 11);
+    /// assert_eq!(clock.witness(&LamportTime(3, Identifier::nil())), clock.time());
+    /// ```
+    ///
+    pub fn witness(&self, other: &LamportTime) -> LamportTime {
+        LamportTime(self.witness_counter(other).1, self.id.clone())
+    }
+
+    /// The `compare_changed` method performs the same update as `compare` and reports whether
+    /// the local counter advanced, i.e. whether the remote time carried new causal information.
+    /// The answer comes from the update itself, so it stays accurate under concurrent updates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportClock, LamportTime};
+    ///
+    /// let clock = LamportClock::new();
+    /// assert!(clock.compare_changed(&LamportTime(10, Identifier::nil())));
+    /// assert!(!clock.compare_changed(&LamportTime(10, Identifier::nil())));
+    /// ```
+    ///
+    pub fn compare_changed(&self, other: &LamportTime) -> bool {
+        let (previous, current) = self.witness_counter(other);
+        current > previous
+    }
+
+    /// Advances the counter past `other` if it is later than the local time, returning the
+    /// counter this call started from and the resulting counter. They are equal when the
+    /// clock did not advance. A remote counter of `u64::MAX` saturates the clock there.
+    fn witness_counter(&self, other: &LamportTime) -> (u64, u64) {
+        let mut current = self.counter.load(Ordering::SeqCst);
+        let next = other.0.saturating_add(1);
         loop {
-            let current_time = LamportTime(self.counter.load(Ordering::SeqCst), self.id.clone());
-            if other_time <= current_time {
-                return;
+            if (other.0, &other.1) <= (current, &self.id) {
+                return (current, current);
             }
 
-            match self.counter.compare_exchange(current_time.0, other_time.0 + 1, Ordering::SeqCst, Ordering::SeqCst) {
-                Ok(_) => break,
-                Err(_) => continue,
+            match self.counter.compare_exchange(current, next, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return (current, next),
+                Err(actual) => current = actual,
             }
         }
     }
 
-    /// Serializes the Lamport clock to bytes.
+    /// The `compare_delta` method behaves like `compare` but returns how far the local
+    /// counter jumped, which measures how far behind this process was. Returns 0 if the
+    /// clock did not advance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{LamportClock, Identifier, LamportTime};
+    ///
+    /// let clock = LamportClock::new();
+    /// let delta = clock.compare_delta(LamportTime(10, Identifier::default()));
+    /// assert_eq!(delta, 10);
+    /// ```
+    ///
+    pub fn compare_delta(&self, other_time: LamportTime) -> u64 {
+        let (previous, current) = self.witness_counter(&other_time);
+        current - previous
+    }
+
+    /// The `is_caught_up_to` method checks whether this clock has reached the counter of a
+    /// read token, regardless of which process issued it.
+    ///
+    /// This supports the read-your-writes pattern: a client keeps the `LamportTime` returned
+    /// by its last write as a token and only reads from replicas that are caught up to it.
+    /// Only the counters are compared, since the token's identifier belongs to another
+    /// process and the identifier tie-break carries no causal meaning here.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let writer = LamportClock::new();
+    /// let replica = LamportClock::new();
+    /// let token = writer.increment();
+    ///
+    /// assert!(!replica.is_caught_up_to(&token));
+    /// replica.compare(token.clone());
+    /// assert!(replica.is_caught_up_to(&token));
+    /// ```
+    ///
+    pub fn is_caught_up_to(&self, token: &LamportTime) -> bool {
+        self.counter.load(Ordering::SeqCst) >= token.0
+    }
+
+    /// Drains every `LamportTime` currently waiting in `rx` without blocking and
+    /// advances the clock once past the highest of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc::channel;
+    /// use logical_clocks_rs::{LamportClock, Identifier, LamportTime};
+    ///
+    /// let clock = LamportClock::new();
+    /// let (tx, rx) = channel();
+    /// tx.send(LamportTime(5, Identifier::default())).unwrap();
+    /// tx.send(LamportTime(9, Identifier::default())).unwrap();
+    /// clock.drain_and_advance(&rx);
+    /// assert_eq!(clock.time().0, 10);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn drain_and_advance(&self, rx: &Receiver<LamportTime>) {
+        if let Some(max_time) = rx.try_iter().max() {
+            self.compare(max_time);
+        }
+    }
+
+    /// Serializes the Lamport clock to bytes: the big-endian `u64` counter followed by the raw
+    /// identifier bytes.
+    ///
+    /// There is no length marker, so the encoding must be the whole buffer. Use
+    /// `to_bytes_framed` to embed a clock inside a larger message.
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
         bytes.extend_from_slice(&self.counter.load(Ordering::SeqCst).to_be_bytes());
@@ -132,7 +731,8 @@ impl LamportClock {
         bytes
     }
 
-    /// Deserializes the Lamport clock from bytes.
+    /// Deserializes the Lamport clock from bytes produced by `to_bytes`, treating everything
+    /// after the counter as the identifier. See `from_bytes_framed` for the framed layout.
     pub fn from_bytes(data: &[u8]) -> Option<Self> {
         if data.len() < 8 {
             return None;
@@ -145,7 +745,379 @@ impl LamportClock {
         })
     }
 
-}
+    /// Serializes the Lamport clock to a self-delimiting layout: the big-endian `u64` counter,
+    /// a big-endian `u32` identifier length and the identifier bytes.
+    ///
+    /// Unlike `to_bytes`, the result can be concatenated with other data and recovered with
+    /// `from_bytes_framed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// let mut message = clock.to_bytes_framed();
+    /// message.extend_from_slice(b"payload");
+    ///
+    /// let (decoded, consumed) = LamportClock::from_bytes_framed(&message).unwrap();
+    /// assert_eq!(decoded.time(), clock.time());
+    /// assert_eq!(&message[consumed..], b"payload");
+    /// ```
+    pub fn to_bytes_framed(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + self.id.0.len());
+        bytes.extend_from_slice(&self.counter.load(Ordering::SeqCst).to_be_bytes());
+        write_identifier(&mut bytes, &self.id);
+        bytes
+    }
+
+    /// Deserializes a Lamport clock from the front of `data` in the layout produced by
+    /// `to_bytes_framed`, returning the clock and the number of bytes consumed.
+    pub fn from_bytes_framed(data: &[u8]) -> Result<(Self, usize), ClockError> {
+        let (count, rest) = read_u64(data)?;
+        let (id, rest) = read_identifier(rest)?;
+        let clock = LamportClock {
+            counter: AtomicU64::new(count),
+            id,
+        };
+        Ok((clock, data.len() - rest.len()))
+    }
+
+    /// The `grant_lease` method grants a lease that stays valid until the clock advances
+    /// `duration_ticks` past its current value, and returns the expiry counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// let expiry = clock.grant_lease(2);
+    /// clock.increment();
+    /// assert!(!clock.lease_expired(expiry));
+    /// clock.increment();
+    /// assert!(clock.lease_expired(expiry));
+    /// ```
+    ///
+    pub fn grant_lease(&self, duration_ticks: u64) -> u64 {
+        self.counter.load(Ordering::SeqCst).saturating_add(duration_ticks)
+    }
+
+    /// Returns true once the clock has reached the `expiry` counter of a lease.
+    pub fn lease_expired(&self, expiry: u64) -> bool {
+        self.counter.load(Ordering::SeqCst) >= expiry
+    }
+
+    /// Returns the raw counter value without the identifier; `peek` and
+    /// `LamportClockReader::counter` read the counter through this method.
+    pub fn load_counter(&self) -> u64 {
+        self.counter.load(Ordering::SeqCst)
+    }
+
+    /// The `store_counter` method stores a raw counter value, e.g. one shared from another
+    /// process, but only if it is greater than the current value. A stale writer can
+    /// therefore never move the clock backwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// clock.store_counter(10);
+    /// clock.store_counter(5);
+    /// assert_eq!(clock.load_counter(), 10);
+    /// ```
+    ///
+    pub fn store_counter(&self, value: u64) {
+        self.counter.fetch_max(value, Ordering::SeqCst);
+    }
+
+    /// The `spawn_child` method creates a clock for a child process that continues from this
+    /// clock's current counter under a fresh random identifier.
+    ///
+    /// Unlike `clone`, which keeps the same identifier, the child has its own identity. Because
+    /// the counter is carried over, the child never emits a stamp earlier than the parent's latest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let parent = LamportClock::new();
+    /// parent.increment();
+    /// let child = parent.spawn_child();
+    /// assert_eq!(child.time().0, parent.time().0);
+    /// assert_ne!(child.time().1, parent.time().1);
+    /// ```
+    ///
+    #[cfg(feature = "std")]
+    pub fn spawn_child(&self) -> LamportClock {
+        LamportClock {
+            counter: AtomicU64::new(self.counter.load(Ordering::SeqCst)),
+            id: Identifier::new(),
+        }
+    }
+
+    /// Returns the clock state as OpenTelemetry-style span attributes: `logical.counter` with
+    /// the current counter and `logical.node` with the identifier's string form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// let attributes = clock.otel_attributes();
+    /// assert_eq!(attributes[0], ("logical.counter", "1".to_string()));
+    /// ```
+    pub fn otel_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("logical.counter", self.counter.load(Ordering::SeqCst).to_string()),
+            ("logical.node", self.id.to_string()),
+        ]
+    }
+
+    /// The `catch_up_to_max` method reconciles this clock with a set of clocks loaded from
+    /// other shards at startup, advancing the counter to one past the highest of them. The
+    /// counter never decreases, so a shard that is legitimately behind has no effect, and a
+    /// shard already at `u64::MAX` saturates the counter there instead of overflowing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// let shard = LamportClock::new();
+    /// shard.store_counter(7);
+    ///
+    /// clock.catch_up_to_max([&shard]);
+    /// assert_eq!(clock.load_counter(), 8);
+    /// ```
+    ///
+    pub fn catch_up_to_max<'a, I: IntoIterator<Item = &'a LamportClock>>(&self, others: I) {
+        if let Some(max) = others.into_iter().map(LamportClock::load_counter).max() {
+            self.counter.fetch_max(max.saturating_add(1), Ordering::SeqCst);
+        }
+    }
+
+    /// The `reader` method returns a read-only view of this clock. The view borrows the clock,
+    /// so it always reflects the owner's latest counter, but it offers no way to advance it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::LamportClock;
+    ///
+    /// let clock = LamportClock::new();
+    /// let reader = clock.reader();
+    /// clock.increment();
+    /// assert_eq!(reader.counter(), 2);
+    /// ```
+    ///
+    pub fn reader(&self) -> LamportClockReader<'_> {
+        LamportClockReader { clock: self }
+    }
+}
+
+/// A read-only view of a `LamportClock`, obtained with `LamportClock::reader`.
+///
+/// Subsystems that hold a reader can observe the clock's time but cannot increment it or
+/// merge remote times into it.
+#[derive(Debug, Clone, Copy)]
+pub struct LamportClockReader<'a> {
+    clock: &'a LamportClock,
+}
+
+impl LamportClockReader<'_> {
+    /// Returns the current time of the underlying clock.
+    pub fn time(&self) -> LamportTime {
+        self.clock.time()
+    }
+
+    /// Returns the current counter of the underlying clock, as `LamportClock::load_counter`.
+    pub fn counter(&self) -> u64 {
+        self.clock.load_counter()
+    }
+
+    /// Returns the identifier of the underlying clock.
+    pub fn id(&self) -> &Identifier {
+        &self.clock.id
+    }
+}
+
+/// A Lamport clock that also remembers the highest counter it has witnessed from each
+/// distinct identifier, for diagnosing convergence.
+///
+/// The scalar counter behaves exactly like `LamportClock`; the per-peer maxima are purely
+/// diagnostic and sit somewhere between a Lamport clock and a vector clock.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "std", derive(Default))]
+pub struct TrackingLamportClock {
+    clock: LamportClock,
+    witnessed: HashMap<Identifier, u64>,
+}
+
+impl TrackingLamportClock {
+    /// Creates a new tracking clock with a default identifier.
+    #[cfg(feature = "std")]
+    pub fn new() -> Self {
+        TrackingLamportClock::default()
+    }
+
+    /// Wraps an existing Lamport clock.
+    pub fn with_clock(clock: LamportClock) -> Self {
+        TrackingLamportClock {
+            clock,
+            witnessed: HashMap::new(),
+        }
+    }
+
+    /// Returns the current value of the Lamport clock.
+    pub fn time(&self) -> LamportTime {
+        self.clock.time()
+    }
+
+    /// Increments the Lamport clock and returns the new value.
+    pub fn increment(&self) -> LamportTime {
+        self.clock.increment()
+    }
+
+    /// Witnesses a remote time like `LamportClock::compare`, recording it as the sender's
+    /// maximum if it is the highest seen from that identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, LamportTime, TrackingLamportClock};
+    ///
+    /// let peer = Identifier::new();
+    /// let mut clock = TrackingLamportClock::new();
+    /// clock.compare(LamportTime(5, peer.clone()));
+    /// assert_eq!(clock.witnessed_maxima().get(&peer), Some(&5));
+    /// ```
+    pub fn compare(&mut self, other_time: LamportTime) {
+        let max = self.witnessed.entry(other_time.1.clone()).or_insert(0);
+        *max = (*max).max(other_time.0);
+        self.clock.compare(other_time);
+    }
+
+    /// Returns the highest counter witnessed from each identifier.
+    pub fn witnessed_maxima(&self) -> &HashMap<Identifier, u64> {
+        &self.witnessed
+    }
+
+    /// Returns the underlying Lamport clock.
+    pub fn clock(&self) -> &LamportClock {
+        &self.clock
+    }
+}
+
+/// A Lamport clock that remembers the last `capacity` times it produced, for inspecting recent
+/// activity after a failure.
+///
+/// Every `increment` and `compare` appends the clock's resulting time to a bounded history; once
+/// the history is full the oldest entry is overwritten. The clock itself behaves exactly like
+/// `LamportClock`.
+///
+/// The history is a mirrored ring buffer: once full, every time is stored at its slot and again
+/// `capacity` slots later, so the last `capacity` times are always one contiguous slice and
+/// recording stays O(1) at the cost of twice the storage.
+#[derive(Debug, Clone)]
+pub struct HistoricalLamportClock {
+    clock: LamportClock,
+    ring: Vec<LamportTime>,
+    head: usize,
+    capacity: usize,
+}
+
+impl HistoricalLamportClock {
+    /// Creates a new clock with a default identifier that remembers up to `capacity` times.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::HistoricalLamportClock;
+    ///
+    /// let mut clock = HistoricalLamportClock::new(2);
+    /// clock.increment();
+    /// clock.increment();
+    /// clock.increment();
+    /// let counters: Vec<u64> = clock.recent().iter().map(|time| time.0).collect();
+    /// assert_eq!(counters, vec![3, 4]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn new(capacity: usize) -> Self {
+        HistoricalLamportClock::with_clock(LamportClock::new(), capacity)
+    }
+
+    /// Wraps an existing Lamport clock, remembering up to `capacity` times.
+    pub fn with_clock(clock: LamportClock, capacity: usize) -> Self {
+        HistoricalLamportClock {
+            clock,
+            ring: Vec::with_capacity(capacity * 2),
+            head: 0,
+            capacity,
+        }
+    }
+
+    /// Returns the current value of the Lamport clock.
+    pub fn time(&self) -> LamportTime {
+        self.clock.time()
+    }
+
+    /// Increments the Lamport clock, records the new value and returns it.
+    pub fn increment(&mut self) -> LamportTime {
+        let time = self.clock.increment();
+        self.record(time.clone());
+        time
+    }
+
+    /// Witnesses a remote time like `LamportClock::compare` and records the resulting time.
+    pub fn compare(&mut self, other_time: LamportTime) {
+        self.clock.compare(other_time);
+        self.record(self.clock.time());
+    }
+
+    /// Returns the recorded times, oldest first.
+    pub fn recent(&self) -> &[LamportTime] {
+        if self.ring.len() < self.capacity * 2 {
+            &self.ring
+        } else {
+            &self.ring[self.head..self.head + self.capacity]
+        }
+    }
+
+    /// Returns the underlying Lamport clock.
+    pub fn clock(&self) -> &LamportClock {
+        &self.clock
+    }
+
+    fn record(&mut self, time: LamportTime) {
+        if self.ring.len() < self.capacity {
+            self.ring.push(time);
+            return;
+        }
+        if self.capacity == 0 {
+            return;
+        }
+        if self.ring.len() < self.capacity * 2 {
+            // The history just filled up: mirror it into the upper half
+            self.ring.extend_from_within(..);
+        }
+        self.ring[self.head] = time.clone();
+        self.ring[self.head + self.capacity] = time;
+        self.head = (self.head + 1) % self.capacity;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for LamportClock {
+    fn default() -> Self {
+        LamportClock::new()
+    }
+}
 
 impl Clone for LamportClock {
     fn clone(&self) -> Self {
@@ -157,14 +1129,113 @@ impl Clone for LamportClock {
 }
 
 
+/// Encodes a batch of Lamport times into a single buffer.
+///
+/// The layout is a big-endian `u32` count followed by, for each time, its `u64` counter and
+/// its identifier prefixed by a `u32` length, so the whole batch shares one header instead of
+/// framing every element separately.
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::{decode_times, encode_times, Identifier, LamportTime};
+///
+/// let times = vec![LamportTime(1, Identifier::new()), LamportTime(2, Identifier::new())];
+/// let bytes = encode_times(&times);
+/// assert_eq!(decode_times(&bytes).unwrap(), times);
+/// ```
+pub fn encode_times(times: &[LamportTime]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(times.len() as u32).to_be_bytes());
+    for time in times {
+        bytes.extend_from_slice(&time.0.to_be_bytes());
+        write_identifier(&mut bytes, &time.1);
+    }
+    bytes
+}
+
+/// Decodes a batch of Lamport times produced by `encode_times`.
+pub fn decode_times(data: &[u8]) -> Result<Vec<LamportTime>, ClockError> {
+    let (count, mut rest) = read_u32(data)?;
+    let mut times = Vec::new();
+
+    for _ in 0..count {
+        let (counter, tail) = read_u64(rest)?;
+        let (id, tail) = read_identifier(tail)?;
+        times.push(LamportTime(counter, id));
+        rest = tail;
+    }
+
+    if !rest.is_empty() {
+        return Err(ClockError::TrailingBytes);
+    }
+    Ok(times)
+}
+
+/// Encodes a group of Lamport clocks, e.g. for a checkpoint, into a single buffer.
+///
+/// The layout is a big-endian `u32` count followed by, for each entry sorted by key, the key
+/// identifier prefixed by a `u32` length, the clock's `u64` counter and the clock's own
+/// identifier prefixed by a `u32` length.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use logical_clocks_rs::{decode_clocks, encode_clocks, Identifier, LamportClock};
+///
+/// let id = Identifier::new();
+/// let clocks = HashMap::from([(id.clone(), LamportClock::with_new_identifier(id.clone()))]);
+/// let decoded = decode_clocks(&encode_clocks(&clocks)).unwrap();
+/// assert_eq!(decoded[&id].time(), clocks[&id].time());
+/// ```
+pub fn encode_clocks(clocks: &HashMap<Identifier, LamportClock>) -> Vec<u8> {
+    let mut entries: Vec<_> = clocks.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+    for (key, clock) in entries {
+        write_identifier(&mut bytes, key);
+        bytes.extend_from_slice(&clock.counter.load(Ordering::SeqCst).to_be_bytes());
+        write_identifier(&mut bytes, &clock.id);
+    }
+    bytes
+}
+
+/// Decodes a group of Lamport clocks produced by `encode_clocks`.
+pub fn decode_clocks(data: &[u8]) -> Result<HashMap<Identifier, LamportClock>, ClockError> {
+    let (count, mut rest) = read_u32(data)?;
+    let mut clocks = HashMap::new();
+
+    for _ in 0..count {
+        let (key, tail) = read_identifier(rest)?;
+        let (counter, tail) = read_u64(tail)?;
+        let (id, tail) = read_identifier(tail)?;
+        clocks.insert(key, LamportClock { counter: AtomicU64::new(counter), id });
+        rest = tail;
+    }
+
+    if !rest.is_empty() {
+        return Err(ClockError::TrailingBytes);
+    }
+    Ok(clocks)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
     use uuid::Uuid;
 
     use crate::Identifier;
 
     use super::*;
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_increment() {
         let clock = LamportClock::new();
@@ -178,6 +1249,7 @@ mod tests {
         assert!(time2 == LamportTime(3, clock.id.clone()));
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_time() {
         let clock = LamportClock::new();
@@ -193,6 +1265,7 @@ mod tests {
         assert!(current_time == LamportTime(4, clock.id.clone()));
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_compare() {
         let clock = LamportClock::new();
@@ -210,6 +1283,7 @@ mod tests {
         assert_eq!(updated_time, LamportTime(11, clock.id.clone()));
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_with_custom_identifier() {
         let custom_id =  Uuid::new_v4().as_bytes().to_vec();
@@ -218,4 +1292,706 @@ mod tests {
         // Check the custom identifier
         assert_eq!(custom_clock.id, Identifier::from_bytes(custom_id.clone()));
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_drain_and_advance() {
+        let clock = LamportClock::new();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        tx.send(LamportTime(4, Identifier::default())).unwrap();
+        tx.send(LamportTime(12, Identifier::default())).unwrap();
+        tx.send(LamportTime(7, Identifier::default())).unwrap();
+
+        clock.drain_and_advance(&rx);
+        assert_eq!(clock.time(), LamportTime(13, clock.id.clone()));
+
+        // An empty channel leaves the clock untouched
+        clock.drain_and_advance(&rx);
+        assert_eq!(clock.time(), LamportTime(13, clock.id.clone()));
+    }
+
+    #[test]
+    fn test_lamport_time_ord_priorities() {
+        let id_a = Identifier::from_bytes(vec![1]);
+        let id_b = Identifier::from_bytes(vec![2]);
+        let id_c = Identifier::from_bytes(vec![3]);
+
+        // By default the identifier bytes break ties
+        assert!(LamportTime(5, id_a.clone()) < LamportTime(5, id_b.clone()));
+
+        // Ranks override the identifier ordering
+        let ord = LamportTimeOrd::with_priorities(HashMap::from([
+            (id_b.clone(), 0),
+            (id_a.clone(), 1),
+        ]));
+        assert_eq!(
+            ord.cmp(&LamportTime(5, id_a.clone()), &LamportTime(5, id_b.clone())),
+            CmpOrdering::Greater
+        );
+
+        // The counter still dominates
+        assert_eq!(
+            ord.cmp(&LamportTime(4, id_a.clone()), &LamportTime(5, id_b.clone())),
+            CmpOrdering::Less
+        );
+
+        let mut times = vec![
+            LamportTime(5, id_c.clone()),
+            LamportTime(5, id_a.clone()),
+            LamportTime(5, id_b.clone()),
+            LamportTime(2, id_c.clone()),
+        ];
+        ord.sort(&mut times);
+        assert_eq!(
+            times,
+            vec![
+                LamportTime(2, id_c.clone()),
+                LamportTime(5, id_b),
+                LamportTime(5, id_a),
+                LamportTime(5, id_c),
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_compare_delta() {
+        let clock = LamportClock::new();
+        clock.increment();
+
+        // Witnessing a time 100 ahead jumps past it
+        let delta = clock.compare_delta(LamportTime(102, Identifier::default()));
+        assert_eq!(delta, 101);
+        assert_eq!(clock.time(), LamportTime(103, clock.id.clone()));
+
+        // An older time does not move the clock
+        let delta = clock.compare_delta(LamportTime(50, Identifier::default()));
+        assert_eq!(delta, 0);
+        assert_eq!(clock.time(), LamportTime(103, clock.id.clone()));
+    }
+
+    #[test]
+    fn test_sortable_string() {
+        let id_a = Identifier::from_bytes(vec![0x01]);
+        let id_b = Identifier::from_bytes(vec![0x01, 0x00]);
+        let id_c = Identifier::from_bytes(vec![0xff]);
+
+        let mut times = Vec::new();
+        for counter in [0, 1, 9, 10, 99, 100, 12_345, u64::MAX] {
+            for id in [&id_a, &id_b, &id_c] {
+                times.push(LamportTime(counter, id.clone()));
+            }
+        }
+        times.reverse();
+
+        let mut by_string = times.clone();
+        by_string.sort_by_key(|time| time.to_sortable_string());
+        times.sort();
+        assert_eq!(by_string, times);
+
+        for time in &times {
+            let decoded = LamportTime::from_sortable_string(&time.to_sortable_string());
+            assert_eq!(decoded.as_ref(), Some(time));
+        }
+
+        assert_eq!(LamportTime::from_sortable_string("42-ab"), None);
+        assert_eq!(LamportTime::from_sortable_string("00000000000000000042-a"), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_is_caught_up_to() {
+        let writer = LamportClock::new();
+        let replica = LamportClock::new();
+
+        writer.increment();
+        let token = writer.increment();
+
+        // The replica has not yet seen the write
+        assert!(!replica.is_caught_up_to(&token));
+        replica.increment();
+        assert!(!replica.is_caught_up_to(&token));
+
+        // Equal counters are caught up regardless of identifier order
+        replica.increment();
+        assert!(replica.is_caught_up_to(&token));
+        assert!(replica.is_caught_up_to(&LamportTime(3, Identifier::from_bytes(vec![0xff; 16]))));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_lease() {
+        let clock = LamportClock::new();
+        let expiry = clock.grant_lease(3);
+        assert_eq!(expiry, 4);
+
+        clock.increment();
+        clock.increment();
+        assert!(!clock.lease_expired(expiry));
+
+        clock.increment();
+        assert!(clock.lease_expired(expiry));
+
+        // Witnessing a remote time can also expire a lease
+        let expiry = clock.grant_lease(100);
+        clock.compare(LamportTime(200, Identifier::default()));
+        assert!(clock.lease_expired(expiry));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_store_counter_is_monotonic() {
+        let clock = LamportClock::new();
+        assert_eq!(clock.load_counter(), 1);
+
+        clock.store_counter(42);
+        assert_eq!(clock.load_counter(), 42);
+
+        // A smaller store is ignored
+        clock.store_counter(7);
+        assert_eq!(clock.load_counter(), 42);
+        assert_eq!(clock.time(), LamportTime(42, clock.id.clone()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_spawn_child() {
+        let parent = LamportClock::new();
+        parent.increment();
+        let latest = parent.increment();
+
+        let child = parent.spawn_child();
+        assert_eq!(child.time().0, latest.0);
+        assert_ne!(child.id, parent.id);
+
+        // The child's next stamp is later than anything the parent emitted
+        assert!(child.increment().0 > latest.0);
+    }
+
+    #[test]
+    fn test_otel_attributes() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let clock = LamportClock::with_new_identifier(Identifier::from_bytes(uuid.as_bytes().to_vec()));
+        clock.increment();
+        clock.increment();
+
+        assert_eq!(
+            clock.otel_attributes(),
+            vec![
+                ("logical.counter", "3".to_string()),
+                ("logical.node", "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_u128_key_order() {
+        let mut ids = Identifier::generate(5);
+        ids.push(Identifier::nil());
+        let mut times = Vec::new();
+        for counter in [0, 1, 2, 1_000, u64::MAX - 1, u64::MAX] {
+            for id in &ids {
+                times.push(LamportTime(counter, id.clone()));
+            }
+        }
+
+        // Identifiers with distinct 8-byte prefixes order exactly like the times
+        for a in &times {
+            for b in &times {
+                assert_eq!(a.to_u128_key().cmp(&b.to_u128_key()), a.cmp(b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_u128_key_never_contradicts_order() {
+        let ids: Vec<Identifier> = [
+            vec![],
+            vec![0],
+            vec![1],
+            vec![1, 0],
+            vec![1, 2],
+            vec![2],
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9],
+            vec![0xff; 16],
+        ]
+        .into_iter()
+        .map(Identifier::from_bytes)
+        .collect();
+        let times: Vec<LamportTime> = [0, 1, u64::MAX]
+            .iter()
+            .flat_map(|&counter| ids.iter().map(move |id| LamportTime(counter, id.clone())))
+            .collect();
+
+        // Colliding prefixes may tie, but a smaller time never gets a larger key
+        for a in &times {
+            for b in &times {
+                if a < b {
+                    assert!(a.to_u128_key() <= b.to_u128_key(), "{} vs {}", a, b);
+                }
+            }
+        }
+        assert_eq!(
+            LamportTime(1, Identifier::from_bytes(vec![1])).to_u128_key(),
+            LamportTime(1, Identifier::from_bytes(vec![1, 0])).to_u128_key()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_catch_up_to_max() {
+        let clock = LamportClock::new();
+        let loaded: Vec<LamportClock> = [5, 12, 8]
+            .iter()
+            .map(|&counter| {
+                let mut bytes = (counter as u64).to_be_bytes().to_vec();
+                bytes.extend_from_slice(&clock.id.0);
+                LamportClock::from_bytes(&bytes).unwrap()
+            })
+            .collect();
+
+        clock.catch_up_to_max(&loaded);
+        assert_eq!(clock.time(), LamportTime(13, clock.id.clone()));
+
+        // Clocks that are behind leave the counter alone
+        clock.catch_up_to_max(&loaded[..1]);
+        assert_eq!(clock.load_counter(), 13);
+
+        clock.catch_up_to_max(std::iter::empty());
+        assert_eq!(clock.load_counter(), 13);
+
+        // A shard at the maximum counter saturates instead of overflowing
+        let shard = LamportClock::new();
+        shard.store_counter(u64::MAX);
+        clock.catch_up_to_max([&shard]);
+        assert_eq!(clock.load_counter(), u64::MAX);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_tracking_witnessed_maxima() {
+        let peer_a = Identifier::new();
+        let peer_b = Identifier::new();
+
+        let mut clock = TrackingLamportClock::new();
+        clock.compare(LamportTime(4, peer_a.clone()));
+        clock.compare(LamportTime(9, peer_b.clone()));
+        clock.compare(LamportTime(2, peer_a.clone()));
+        clock.compare(LamportTime(6, peer_a.clone()));
+
+        assert_eq!(
+            clock.witnessed_maxima(),
+            &HashMap::from([(peer_a.clone(), 6), (peer_b.clone(), 9)])
+        );
+
+        // The scalar counter still follows the Lamport rules
+        assert_eq!(clock.time().0, 10);
+        assert_eq!(clock.increment().0, 11);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_encode_decode_times() {
+        let times = vec![
+            LamportTime(1, Identifier::new()),
+            LamportTime(42, Identifier::from_bytes(vec![9, 8, 7])),
+            LamportTime(u64::MAX, Identifier::from_bytes(Vec::new())),
+            LamportTime(7, Identifier::from_name("node-1")),
+        ];
+
+        let bytes = encode_times(&times);
+        assert_eq!(decode_times(&bytes).unwrap(), times);
+        assert_eq!(decode_times(&encode_times(&[])).unwrap(), Vec::new());
+
+        assert_eq!(decode_times(&bytes[..bytes.len() - 1]), Err(ClockError::Truncated));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(decode_times(&trailing), Err(ClockError::TrailingBytes));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader_reflects_owner_updates() {
+        let clock = LamportClock::new();
+        let reader = clock.reader();
+        assert_eq!(reader.counter(), 1);
+
+        clock.increment();
+        clock.compare(LamportTime(10, Identifier::new()));
+        assert_eq!(reader.counter(), 11);
+        assert_eq!(reader.time(), clock.time());
+        assert_eq!(reader.id(), &clock.time().1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_decrement_concurrent() {
+        let clock = Arc::new(LamportClock::new());
+        clock.store_counter(100);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let clock = Arc::clone(&clock);
+                thread::spawn(move || (0..50).filter(|_| clock.decrement().is_some()).count())
+            })
+            .collect();
+        let undone: usize = handles.into_iter().map(|handle| handle.join().unwrap()).sum();
+
+        assert_eq!(undone, 99);
+        assert_eq!(clock.load_counter(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_peek_does_not_advance() {
+        let clock = LamportClock::new();
+        assert_eq!(clock.peek(), 1);
+        assert_eq!(clock.peek(), 1);
+
+        clock.compare(LamportTime(7, Identifier::nil()));
+        assert_eq!(clock.peek(), 8);
+        assert_eq!(clock.time().0, 8);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_timestamped_orders_by_lamport_time() {
+        let clock = LamportClock::new();
+        let mut earlier = clock.increment_timestamped();
+        let later = clock.increment_timestamped();
+
+        // Pretend the earlier stamp came from a machine whose wall clock runs ahead
+        earlier.wall = later.wall + Duration::from_secs(3600);
+        assert!(earlier < later);
+
+        let mut same = later.clone();
+        same.wall = SystemTime::UNIX_EPOCH;
+        assert_eq!(same, later);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_send_receive_happens_before() {
+        let a = LamportClock::new();
+        let b = LamportClock::new();
+        b.store_counter(20);
+
+        let to_b = a.send();
+        let received = b.receive(&to_b);
+        assert!(to_b.0 < received.0);
+        assert_eq!(received.0, 21);
+
+        let to_a = b.send();
+        let received = a.receive(&to_a);
+        assert!(to_a.0 < received.0);
+        assert_eq!(received, a.time());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_receive_concurrent() {
+        let clock = Arc::new(LamportClock::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let clock = Arc::clone(&clock);
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        clock.receive(&LamportTime(0, Identifier::nil()));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Every receive is its own event, so none are lost
+        assert_eq!(clock.peek(), 401);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_receive_saturates_at_max() {
+        let clock = LamportClock::new();
+        let time = clock.receive(&LamportTime(u64::MAX, Identifier::nil()));
+        assert_eq!(time.0, u64::MAX);
+        assert_eq!(clock.receive(&LamportTime(0, Identifier::nil())).0, u64::MAX);
+    }
+
+    #[test]
+    fn test_lamport_time_display() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let time = LamportTime(42, Identifier::from_bytes(uuid.as_bytes().to_vec()));
+        assert_eq!(time.to_string(), "42@67e55044-10b1-426f-9247-bb680e5fe0c8");
+    }
+
+    #[test]
+    fn test_encode_decode_clocks() {
+        let clocks: HashMap<Identifier, LamportClock> = (0..5)
+            .map(|i| {
+                let id = Identifier::from_bytes(vec![i; i as usize + 1]);
+                let clock = LamportClock::with_new_identifier(id.clone());
+                clock.store_counter(u64::from(i) * 10);
+                (id, clock)
+            })
+            .collect();
+
+        let bytes = encode_clocks(&clocks);
+        let decoded = decode_clocks(&bytes).unwrap();
+        assert_eq!(decoded.len(), clocks.len());
+        for (id, clock) in &clocks {
+            assert_eq!(decoded[id].time(), clock.time());
+        }
+
+        assert_eq!(decode_clocks(&bytes[..bytes.len() - 1]).err(), Some(ClockError::Truncated));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(decode_clocks(&trailing).err(), Some(ClockError::TrailingBytes));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_lamport_time_golden_bytes() {
+        let time = LamportTime(0x0102, Identifier::from_bytes(vec![0xab, 0xcd]));
+        let bytes = time.to_bytes().unwrap();
+        assert_eq!(
+            bytes,
+            vec![0x02, 0x01, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0xab, 0xcd]
+        );
+        assert_eq!(LamportTime::from_bytes(&bytes).unwrap(), time);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_historical_keeps_last_n() {
+        const N: usize = 4;
+        let mut clock = HistoricalLamportClock::new(N);
+        for _ in 0..N + 2 {
+            clock.increment();
+        }
+
+        let counters: Vec<u64> = clock.recent().iter().map(|time| time.0).collect();
+        assert_eq!(counters, vec![4, 5, 6, 7]);
+
+        clock.compare(LamportTime(20, Identifier::nil()));
+        assert_eq!(clock.recent().len(), N);
+        assert_eq!(clock.recent().last(), Some(&clock.time()));
+        assert_eq!(clock.time().0, 21);
+
+        let mut disabled = HistoricalLamportClock::new(0);
+        disabled.increment();
+        assert!(disabled.recent().is_empty());
+    }
+
+    #[test]
+    fn test_historical_wraps_around() {
+        const N: usize = 3;
+        let clock = LamportClock::with_new_identifier(Identifier::from_bytes(vec![1]));
+        let mut historical = HistoricalLamportClock::with_clock(clock, N);
+
+        // Several full turns of the ring, checking the window after every record
+        for _ in 0..N * 3 + 1 {
+            let latest = historical.increment().0;
+            let expected: Vec<u64> = (latest.saturating_sub(N as u64 - 1).max(2)..=latest).collect();
+            let counters: Vec<u64> = historical.recent().iter().map(|time| time.0).collect();
+            assert_eq!(counters, expected);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_checked_increment_at_max() {
+        let mut bytes = u64::MAX.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&Identifier::new().0);
+        let clock = LamportClock::from_bytes(&bytes).unwrap();
+
+        assert_eq!(clock.checked_increment(), None);
+        assert_eq!(clock.peek(), u64::MAX);
+
+        let clock = LamportClock::new();
+        clock.store_counter(u64::MAX - 1);
+        assert_eq!(clock.checked_increment().map(|time| time.0), Some(u64::MAX));
+        assert_eq!(clock.checked_increment(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_stamp_header() {
+        let clock = LamportClock::new();
+        clock.store_counter(41);
+
+        let header = clock.stamp_header();
+        let decoded = LamportTime::from_bytes(&header).unwrap();
+        assert_eq!(decoded.0, 42);
+        assert_eq!(decoded, clock.time());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_framed_bytes_embedded_in_message() {
+        let first = LamportClock::with_custom_identifier(vec![1, 2, 3]);
+        first.store_counter(9);
+        let second = LamportClock::new();
+
+        let mut message = first.to_bytes_framed();
+        message.extend_from_slice(&second.to_bytes_framed());
+        message.extend_from_slice(b"tail");
+
+        let (decoded, consumed) = LamportClock::from_bytes_framed(&message).unwrap();
+        assert_eq!(consumed, 8 + 4 + 3);
+        assert_eq!(decoded.time(), first.time());
+
+        let (decoded, used) = LamportClock::from_bytes_framed(&message[consumed..]).unwrap();
+        assert_eq!(decoded.time(), second.time());
+        assert_eq!(&message[consumed + used..], b"tail");
+
+        assert_eq!(
+            LamportClock::from_bytes_framed(&message[..10]).err(),
+            Some(ClockError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_tie_break() {
+        let ids: Vec<Identifier> = (1..=8).map(|len| Identifier::from_bytes(vec![0x5a; len])).collect();
+        let ord = LamportTimeOrd::by_fingerprint();
+
+        let mut times: Vec<LamportTime> = ids.iter().map(|id| LamportTime(3, id.clone())).collect();
+        times.push(LamportTime(2, ids[7].clone()));
+        ord.sort(&mut times);
+
+        // Counters still come first
+        assert_eq!(times[0], LamportTime(2, ids[7].clone()));
+        for pair in times[1..].windows(2) {
+            assert!(pair[0].1.fingerprint() <= pair[1].1.fingerprint());
+            assert_eq!(ord.cmp(&pair[0], &pair[1]), CmpOrdering::Less);
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_lamport_time_json_round_trip() {
+        for id in [Identifier::new(), Identifier::from_bytes(vec![7; 5])] {
+            let time = LamportTime(u64::MAX, id);
+            assert_eq!(LamportTime::from_json(&time.to_json()).unwrap(), time);
+        }
+        assert!(matches!(
+            LamportTime::from_json(r#"{"counter":1}"#),
+            Err(ClockError::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn test_stamped_events_sort_deterministically() {
+        let a = LamportClock::with_custom_identifier(vec![1]);
+        let b = LamportClock::with_custom_identifier(vec![2]);
+
+        let mut log = vec![
+            StampedEvent::record(&b, "b2"),
+            StampedEvent::record(&a, "a2"),
+            StampedEvent::record(&b, "b3"),
+        ];
+        log.insert(0, StampedEvent { time: LamportTime(1, Identifier::from_bytes(vec![2])), payload: "b1" });
+        StampedEvent::sort_by_time(&mut log);
+
+        // Equal counters fall back to the identifier bytes
+        let payloads: Vec<&str> = log.iter().map(|event| event.payload).collect();
+        assert_eq!(payloads, vec!["b1", "a2", "b2", "b3"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_lamport_clock_serde_counter_encoding() {
+        let clock = LamportClock::with_new_identifier(Identifier::from_bytes(vec![7]));
+        clock.increment();
+
+        // The counter is encoded as a plain u64, as serde's std atomic impl did
+        let bytes = bincode::serialize(&clock).unwrap();
+        assert_eq!(bytes, bincode::serialize(&(2u64, vec![7u8])).unwrap());
+
+        let decoded: LamportClock = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.time(), clock.time());
+    }
+
+    #[test]
+    fn test_witness_returns_updated_time() {
+        let id = Identifier::from_bytes(vec![5]);
+        let clock = LamportClock::with_new_identifier(id.clone());
+
+        assert_eq!(clock.witness(&LamportTime(7, Identifier::from_bytes(vec![9]))), LamportTime(8, id.clone()));
+        // Equal counters only advance the clock when the remote identifier wins the tie
+        assert_eq!(clock.witness(&LamportTime(8, Identifier::from_bytes(vec![1]))), LamportTime(8, id.clone()));
+        assert_eq!(clock.witness(&LamportTime(8, Identifier::from_bytes(vec![9]))), LamportTime(9, id.clone()));
+        assert_eq!(clock.witness(&LamportTime(2, Identifier::from_bytes(vec![9]))), clock.time());
+
+        let shared = Arc::new(LamportClock::with_new_identifier(id));
+        let handles: Vec<_> = (0..4u64)
+            .map(|i| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    for n in 0..100 {
+                        let time = shared.witness(&LamportTime(n * 4 + i, Identifier::from_bytes(vec![9])));
+                        assert!(time.0 > n * 4 + i);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(shared.time().0, 400);
+    }
+
+    #[test]
+    fn test_compare_changed_under_concurrency() {
+        let clock = Arc::new(LamportClock::with_new_identifier(Identifier::from_bytes(vec![5])));
+        let remote = LamportTime(50, Identifier::from_bytes(vec![9]));
+
+        // Every thread witnesses the same remote time; exactly one of them advances the clock
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let (clock, remote) = (Arc::clone(&clock), remote.clone());
+                thread::spawn(move || clock.compare_changed(&remote))
+            })
+            .collect();
+        let changed = handles.into_iter().map(|h| h.join().unwrap()).filter(|&c| c).count();
+        assert_eq!(changed, 1);
+        assert_eq!(clock.time().0, 51);
+
+        clock.increment();
+        assert!(!clock.compare_changed(&LamportTime(52, Identifier::from_bytes(vec![1]))));
+        assert!(clock.compare_changed(&LamportTime(52, Identifier::from_bytes(vec![9]))));
+    }
+
+    #[test]
+    fn test_witness_saturates_at_max() {
+        let id = Identifier::from_bytes(vec![5]);
+        let clock = LamportClock::with_new_identifier(id.clone());
+        let remote = LamportTime(u64::MAX, Identifier::from_bytes(vec![9]));
+
+        assert_eq!(clock.witness(&remote), LamportTime(u64::MAX, id));
+        // The remote identifier still wins the tie, but the clock cannot move past the maximum
+        assert!(!clock.compare_changed(&remote));
+        assert_eq!(clock.compare_delta(remote), 0);
+        assert_eq!(clock.peek(), u64::MAX);
+    }
+
+    #[test]
+    fn test_lamport_time_hash_dedup() {
+        use std::collections::HashSet;
+
+        let id = Identifier::from_bytes(vec![1]);
+        let times = [
+            LamportTime(3, id.clone()),
+            LamportTime(3, Identifier::from_bytes(vec![1])),
+            LamportTime(4, id.clone()),
+            LamportTime(3, Identifier::from_bytes(vec![2])),
+        ];
+        let unique: HashSet<LamportTime> = times.iter().cloned().collect();
+        assert_eq!(unique.len(), 3);
+        assert!(unique.contains(&LamportTime(3, id)));
+    }
+}
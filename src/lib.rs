@@ -1,7 +1,52 @@
-pub use crate::lamport_clock::{LamportClock, LamportTime};
-pub use crate::identifier::Identifier;
-pub use crate::vclock::{VClock, VClockTime, Vector};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+pub use crate::lamport_clock::{
+    decode_clocks, decode_times, encode_clocks, encode_times, HistoricalLamportClock, LamportClock,
+    LamportClockReader, LamportTime, LamportTimeOrd, StampedEvent, TrackingLamportClock,
+};
+#[cfg(feature = "std")]
+pub use crate::lamport_clock::TimestampedLamportTime;
+pub use crate::broadcast::{CausalBroadcast, CausalBuffer};
+pub use crate::concurrent::{ConcurrentVClock, CounterOrdering, SyncVClock};
+pub use crate::crdt::{GCounter, PNCounter};
+pub use crate::dag::{CausalDag, EventId};
+pub use crate::dense::DenseVClock;
+pub use crate::dot::to_hasse_dot;
+pub use crate::error::ClockError;
+#[cfg(feature = "std")]
+pub use crate::hlc::{HlcTime, HybridClock, DEFAULT_MAX_DRIFT_MS};
+pub use crate::identifier::{anonymize, deanonymize, Identifier, IdentifierRegistry};
+pub use crate::itc::Stamp;
+pub use crate::matrix::MatrixClock;
+pub use crate::stability::CausalStabilityTracker;
+pub use crate::vclock::{
+    causal_sort, detect_split_brain, frontier, peak_concurrency, AuditedVClock, BoundedVClock,
+    CausalMap, ConflictCountingVClock, HashedVClock, Operation, VClock, VClockHistory, VClockIter,
+    VClockSet, VClockTime, Vector,
+};
+#[cfg(feature = "async")]
+pub use crate::vclock::fold_clocks;
+#[cfg(feature = "json")]
+pub use crate::timeline::timeline_json;
+
+mod broadcast;
+mod codec;
+mod compat;
+mod concurrent;
+mod crdt;
+mod dag;
+mod dense;
+mod dot;
+mod error;
+#[cfg(feature = "std")]
+mod hlc;
+mod itc;
 mod lamport_clock;
+mod matrix;
 mod identifier;
+mod stability;
+#[cfg(feature = "json")]
+mod timeline;
 mod vclock;
\ No newline at end of file
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::compat::HashMap;
+use crate::{Identifier, VClock};
+
+/// A matrix clock: for every node, the latest vector clock known from it
+///
+/// Row `j` holds what this node knows about node `j`'s vector clock, and the
+/// local node's own row is its vector clock. The minimum of a column across
+/// all rows is a counter every node is known to have seen, which is the
+/// watermark below which messages from that node can be garbage collected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixClock {
+    rows: HashMap<Identifier, HashMap<Identifier, u64>>,
+}
+
+impl MatrixClock {
+    /// Creates a new empty matrix clock
+    pub fn new() -> Self {
+        MatrixClock::default()
+    }
+
+    /// Creates a matrix clock with an empty row for each member, so that
+    /// members not heard from yet hold the watermark at 0
+    pub fn with_members<I: IntoIterator<Item = Identifier>>(members: I) -> Self {
+        MatrixClock {
+            rows: members.into_iter().map(|member| (member, HashMap::new())).collect(),
+        }
+    }
+
+    /// Records a local event on node `me`, incrementing its own entry in its
+    /// own row
+    pub fn increment(&mut self, me: &Identifier) {
+        let row = self.rows.entry(me.clone()).or_default();
+        *row.entry(me.clone()).or_insert(0) += 1;
+    }
+
+    /// Merges the vector clock last reported by `from` into its row
+    ///
+    /// Reports are merged by component-wise maximum, so stale or reordered
+    /// reports never move a row backwards.
+    pub fn update(&mut self, from: &Identifier, their_vclock: &VClock) {
+        let row = self.rows.entry(from.clone()).or_default();
+        for (node, counter) in their_vclock {
+            let entry = row.entry(node.clone()).or_insert(0);
+            *entry = (*entry).max(counter);
+        }
+    }
+
+    /// Returns the lowest counter for `node` across all rows, i.e. the number
+    /// of `node`'s events every known node has seen
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, MatrixClock, VClock};
+    ///
+    /// let (me, peer) = (Identifier::new(), Identifier::new());
+    /// let mut matrix = MatrixClock::with_members([me.clone(), peer.clone()]);
+    /// matrix.increment(&me);
+    /// assert_eq!(matrix.min_known(&me), 0);
+    ///
+    /// let mut seen = VClock::new();
+    /// seen.increment(&me);
+    /// matrix.update(&peer, &seen);
+    /// assert_eq!(matrix.min_known(&me), 1);
+    /// ```
+    pub fn min_known(&self, node: &Identifier) -> u64 {
+        self.rows
+            .values()
+            .map(|row| *row.get(node).unwrap_or(&0))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Returns the row for `node`, i.e. the latest vector clock known from it
+    pub fn row(&self, node: &Identifier) -> VClock {
+        VClock {
+            vector: self.rows.get(node).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix_watermark_needs_every_row() {
+        let ids = Identifier::generate(3);
+        let mut matrix = MatrixClock::with_members(ids.clone());
+
+        for _ in 0..3 {
+            matrix.increment(&ids[0]);
+        }
+        assert_eq!(matrix.row(&ids[0]).get(&ids[0]), 3);
+        assert_eq!(matrix.min_known(&ids[0]), 0);
+
+        let mut seen = VClock::new();
+        seen.increment(&ids[0]);
+        seen.increment(&ids[0]);
+        matrix.update(&ids[1], &seen);
+        // ids[2] has not reported yet
+        assert_eq!(matrix.min_known(&ids[0]), 0);
+
+        seen.increment(&ids[0]);
+        matrix.update(&ids[2], &seen);
+        assert_eq!(matrix.min_known(&ids[0]), 2);
+
+        matrix.update(&ids[1], &seen);
+        assert_eq!(matrix.min_known(&ids[0]), 3);
+
+        // A stale report never lowers the watermark
+        matrix.update(&ids[1], &VClock::new());
+        assert_eq!(matrix.min_known(&ids[0]), 3);
+    }
+}
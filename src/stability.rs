@@ -0,0 +1,176 @@
+use alloc::vec::Vec;
+
+use crate::compat::{HashMap, HashSet};
+use crate::{Identifier, VClockTime};
+
+/// Tracks pending events and reports them once they become causally stable
+///
+/// An event is causally stable once every peer has seen it, i.e. it is `<=`
+/// the latest clock reported by each peer. The tracker keeps the last clock of
+/// every peer, derives the component-wise minimum of those clocks (the
+/// stability watermark) and invokes `on_stable` for each pending event the
+/// watermark has caught up with. This is the usual trigger for garbage
+/// collecting messages that no peer can still ask for.
+pub struct CausalStabilityTracker<F: FnMut(VClockTime)> {
+    peers: HashMap<Identifier, VClockTime>,
+    pending: Vec<VClockTime>,
+    on_stable: F,
+}
+
+impl<F: FnMut(VClockTime)> CausalStabilityTracker<F> {
+    /// Creates a tracker for the given peer set that calls `on_stable` whenever
+    /// a tracked event becomes stable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{CausalStabilityTracker, Identifier, VClock};
+    ///
+    /// let peer = Identifier::new();
+    /// let mut stable = Vec::new();
+    /// let mut tracker = CausalStabilityTracker::new(vec![peer.clone()], |event| stable.push(event));
+    ///
+    /// let mut event = VClock::new();
+    /// event.increment(&peer);
+    /// tracker.track(event.time());
+    /// tracker.update(&peer, &event.time());
+    /// drop(tracker);
+    /// assert_eq!(stable.len(), 1);
+    /// ```
+    pub fn new<I: IntoIterator<Item = Identifier>>(peers: I, on_stable: F) -> Self {
+        CausalStabilityTracker {
+            peers: peers
+                .into_iter()
+                .map(|peer| (peer, VClockTime(HashMap::new())))
+                .collect(),
+            pending: Vec::new(),
+            on_stable,
+        }
+    }
+
+    /// Starts tracking an event, firing immediately if it is already stable
+    pub fn track(&mut self, event: VClockTime) {
+        self.pending.push(event);
+        self.release();
+    }
+
+    /// Records the latest clock reported by `peer` and fires `on_stable` for
+    /// every pending event that became stable as a result
+    ///
+    /// Reports are merged with what is already known about the peer, so stale
+    /// or reordered updates never move the watermark backwards. Updates from
+    /// identifiers outside the peer set are ignored.
+    pub fn update(&mut self, peer: &Identifier, clock: &VClockTime) {
+        let Some(known) = self.peers.get_mut(peer) else {
+            return;
+        };
+        for (node, &counter) in &clock.0 {
+            let entry = known.0.entry(node.clone()).or_insert(0);
+            *entry = (*entry).max(counter);
+        }
+        self.release();
+    }
+
+    /// Returns the component-wise minimum of all peer clocks
+    pub fn watermark(&self) -> VClockTime {
+        let nodes: HashSet<_> = self.peers.values().flat_map(|clock| clock.0.keys()).collect();
+        VClockTime(
+            nodes
+                .into_iter()
+                .map(|node| {
+                    let min = self
+                        .peers
+                        .values()
+                        .map(|clock| *clock.0.get(node).unwrap_or(&0))
+                        .min()
+                        .unwrap_or(0);
+                    (node.clone(), min)
+                })
+                .filter(|(_, counter)| *counter > 0)
+                .collect(),
+        )
+    }
+
+    /// Returns the events that are not yet stable
+    pub fn pending(&self) -> &[VClockTime] {
+        &self.pending
+    }
+
+    fn release(&mut self) {
+        if self.peers.is_empty() {
+            return;
+        }
+        let watermark = self.watermark();
+        let (stable, pending): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|event| *event <= watermark);
+        self.pending = pending;
+        for event in stable {
+            (self.on_stable)(event);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::VClock;
+
+    #[test]
+    fn test_stability_fires_on_last_peer() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::new();
+
+        let mut stable = Vec::new();
+        let mut tracker = CausalStabilityTracker::new(
+            vec![id1.clone(), id2.clone(), id3.clone()],
+            |event| stable.push(event),
+        );
+
+        let mut event = VClock::new();
+        event.increment(&id1);
+        tracker.track(event.time());
+
+        let mut later = event.clone();
+        later.increment(&id2);
+
+        tracker.update(&id1, &later.time());
+        tracker.update(&id2, &event.time());
+        assert_eq!(tracker.pending().len(), 1);
+
+        // The lagging peer finally catches up
+        tracker.update(&id3, &later.time());
+        assert!(tracker.pending().is_empty());
+
+        drop(tracker);
+        assert_eq!(stable, vec![event.time()]);
+    }
+
+    #[test]
+    fn test_stability_watermark() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut tracker = CausalStabilityTracker::new(vec![id1.clone(), id2.clone()], |_| {});
+
+        let mut a = VClock::new();
+        a.increment(&id1);
+        a.increment(&id1);
+        let mut b = VClock::new();
+        b.increment(&id1);
+        b.increment(&id2);
+
+        tracker.update(&id1, &a.time());
+        tracker.update(&id2, &b.time());
+
+        let watermark = tracker.watermark();
+        assert_eq!(watermark.0.get(&id1), Some(&1));
+        assert_eq!(watermark.0.get(&id2), None);
+
+        // A stale report does not move the watermark backwards
+        tracker.update(&id1, &VClock::new().time());
+        assert_eq!(tracker.watermark(), watermark);
+    }
+}
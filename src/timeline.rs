@@ -0,0 +1,82 @@
+use serde_json::{json, Map, Value};
+
+use crate::vclock::covering_edges;
+use crate::{ClockError, VClockTime};
+
+/// Exports a set of named snapshots as a JSON array of timeline events
+///
+/// Each event is an object `{"name": ..., "clock": {...}, "depends_on": [...]}`
+/// in input order. `clock` maps the string form of each identifier to its
+/// counter, and `depends_on` lists the names of the directly preceding events,
+/// i.e. the covering edges of the happened-before relation, in input order.
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::{timeline_json, Identifier, VClock};
+///
+/// let id = Identifier::new();
+/// let mut vclock = VClock::new();
+/// vclock.increment(&id);
+/// let a = vclock.time();
+/// vclock.increment(&id);
+/// let b = vclock.time();
+///
+/// let json = timeline_json(&[("a", &a), ("b", &b)]).unwrap();
+/// assert!(json.contains(r#""depends_on":["a"]"#));
+/// ```
+pub fn timeline_json(events: &[(&str, &VClockTime)]) -> Result<String, ClockError> {
+    let times: Vec<&VClockTime> = events.iter().map(|(_, time)| *time).collect();
+    let mut depends_on = vec![Vec::new(); events.len()];
+    for (from, to) in covering_edges(&times) {
+        depends_on[to].push(from);
+    }
+    for parents in &mut depends_on {
+        parents.sort_unstable();
+    }
+
+    let timeline: Vec<Value> = events
+        .iter()
+        .zip(depends_on)
+        .map(|((name, time), parents)| {
+            let clock: Map<String, Value> = time
+                .0
+                .iter()
+                .map(|(node, &counter)| (node.to_string(), json!(counter)))
+                .collect();
+            let parents: Vec<&str> = parents.into_iter().map(|index| events[index].0).collect();
+            json!({ "name": name, "clock": clock, "depends_on": parents })
+        })
+        .collect();
+
+    serde_json::to_string(&timeline).map_err(|err| ClockError::Serialization(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Identifier, VClock};
+
+    #[test]
+    fn test_timeline_json_linear_chain() {
+        let id = Identifier::new();
+        let mut vclock = VClock::new();
+        let mut times = Vec::new();
+        for _ in 0..3 {
+            vclock.increment(&id);
+            times.push(vclock.time());
+        }
+
+        let json = timeline_json(&[("c", &times[2]), ("a", &times[0]), ("b", &times[1])]).unwrap();
+        let parsed: Value = serde_json::from_str(&json).unwrap();
+        let node = id.to_string();
+        assert_eq!(
+            parsed,
+            json!([
+                { "name": "c", "clock": { node.clone(): 3 }, "depends_on": ["b"] },
+                { "name": "a", "clock": { node.clone(): 1 }, "depends_on": [] },
+                { "name": "b", "clock": { node: 2 }, "depends_on": ["a"] },
+            ])
+        );
+    }
+}
@@ -1,9 +1,18 @@
-use std::collections::{HashMap, HashSet};
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
-use bincode::{self, Error as BincodeError};
-use std::cmp::Ordering;
+#[cfg(feature = "std")]
+use bincode::{Error as BincodeError, Options};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::BitOr;
 
-use crate::Identifier;
+#[cfg(feature = "std")]
+use crate::codec::bincode_options;
+use crate::codec::{read_identifier, read_u32, read_u64, write_identifier};
+use crate::compat::{hash_map, Fnv1aHasher, HashMap, HashSet};
+use crate::{ClockError, Identifier};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Vector {
@@ -38,20 +47,49 @@ impl Vector {
     /// vector.add(id);
     /// ```
     pub fn add(&mut self, id: Identifier) -> &mut Self {
-        self.data.insert(id, 0);
+        self.add_with(id, 0)
+    }
+
+    /// Adds an identifier to the vector with a known starting counter, e.g. when
+    /// bootstrapping a replica from a snapshot
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock, Vector};
+    ///
+    /// let (a, b) = (Identifier::new(), Identifier::new());
+    /// let mut vector = Vector::new();
+    /// vector.add_with(a.clone(), 7).add(b.clone());
+    ///
+    /// let time = VClock::with_vector(vector).time();
+    /// assert_eq!(time.0[&a], 7);
+    /// assert_eq!(time.0[&b], 0);
+    /// ```
+    pub fn add_with(&mut self, id: Identifier, counter: u64) -> &mut Self {
+        self.data.insert(id, counter);
         self
     }
 
     /// Converts the vector to a `HashMap`
-    fn to_hashmap(self) -> HashMap<Identifier, u64> {
+    pub(crate) fn into_hashmap(self) -> HashMap<Identifier, u64> {
         self.data
     }
 }
 
+impl Default for Vector {
+    fn default() -> Self {
+        Vector::new()
+    }
+}
+
 /// Represents a vector clock
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+///
+/// Equality treats a missing entry as equal to a zero-valued one, matching the
+/// comparison semantics used throughout the crate.
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VClock {
-    vector: HashMap<Identifier, u64>,
+    pub(crate) vector: HashMap<Identifier, u64>,
 }
 
 impl VClock {
@@ -81,7 +119,7 @@ impl VClock {
     /// let vclock = VClock::with_vector(vector);
     /// ```
     pub fn with_vector(vector: Vector) -> Self {
-        VClock { vector: vector.to_hashmap() }
+        VClock { vector: vector.into_hashmap() }
     }
 
     /// Increments the logical clock for the current node
@@ -102,6 +140,14 @@ impl VClock {
 
     /// Merges another vector clock into this one
     ///
+    /// Merging takes the component-wise maximum, which makes it the join of the
+    /// vector clock lattice. For any clocks `a`, `b` and `c` it is:
+    ///
+    /// - commutative: `a` merged with `b` equals `b` merged with `a`
+    /// - associative: merging `b` then `c` into `a` equals merging `a` with the
+    ///   merge of `b` and `c`
+    /// - idempotent: merging `a` into itself leaves it unchanged
+    ///
     /// # Examples
     ///
     /// ```
@@ -112,9 +158,40 @@ impl VClock {
     /// vclock1.merge(&vclock2);
     /// ```
     pub fn merge(&mut self, other: &VClock) {
-        for (node, &counter) in &other.vector {
+        self.merge_with(other, |_, self_counter, other_counter| self_counter.max(other_counter));
+    }
+
+    /// Merges another vector clock into this one using a custom resolver
+    ///
+    /// `resolver` is called with `(node, self_counter, other_counter)` for every
+    /// node known to either clock (a missing entry counts as 0) and its result is
+    /// stored. `merge` is `merge_with` using `max`; other resolvers are useful for
+    /// specialized CRDTs layered on the vector but need not preserve the merge
+    /// laws.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock1 = VClock::new();
+    /// let mut vclock2 = VClock::new();
+    /// vclock1.increment(&id);
+    /// vclock2.increment(&id);
+    ///
+    /// vclock1.merge_with(&vclock2, |_, a, b| a + b);
+    /// assert_eq!(vclock1.time().0[&id], 2);
+    /// ```
+    pub fn merge_with<F: FnMut(&Identifier, u64, u64) -> u64>(&mut self, other: &VClock, mut resolver: F) {
+        for (node, counter) in self.vector.iter_mut() {
+            if !other.vector.contains_key(node) {
+                *counter = resolver(node, *counter, 0);
+            }
+        }
+        for (node, &other_counter) in &other.vector {
             let entry = self.vector.entry(node.clone()).or_insert(0);
-            *entry = (*entry).max(counter);
+            *entry = resolver(node, *entry, other_counter);
         }
     }
 
@@ -165,174 +242,3667 @@ impl VClock {
     pub fn time(&self) -> VClockTime {
         VClockTime(self.vector.clone())
     }
-}
-
-impl PartialOrd for VClockTime {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let mut is_less = false;
-        let mut is_greater = false;
-
-        let keys: HashSet<_> = self.0.keys().chain(other.0.keys()).collect();
 
-        for key in keys {
-            let self_counter = self.0.get(key).unwrap_or(&0);
-            let other_counter = other.0.get(key).unwrap_or(&0);
+    /// Checks if this vector clock is a legal successor of `previous`
+    ///
+    /// Every logged step is either a local increment, which advances one node
+    /// by exactly one, or a receive, which merges a peer's entries and then
+    /// advances the local node by exactly one. A valid successor therefore
+    /// dominates `previous` on every node and has at least one entry that rose
+    /// by exactly one; other entries may only jump as part of such a merge
+    /// step. A regression, a record identical to its predecessor or a lone jump
+    /// points to a corrupted or duplicated log entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{VClock, Identifier};
+    ///
+    /// let id = Identifier::new();
+    /// let previous = VClock::new();
+    /// let mut next = previous.clone();
+    /// next.increment(&id);
+    /// assert!(next.is_valid_successor(&previous));
+    /// assert!(!previous.is_valid_successor(&next));
+    /// ```
+    pub fn is_valid_successor(&self, previous: &VClock) -> bool {
+        let mut ticked = false;
 
-            if self_counter < other_counter {
-                is_less = true;
-            }
-            if self_counter > other_counter {
-                is_greater = true;
+        for (node, &previous_counter) in &previous.vector {
+            if *self.vector.get(node).unwrap_or(&0) < previous_counter {
+                return false;
             }
+        }
 
-            if is_less && is_greater {
-                return None;
+        for (node, &self_counter) in &self.vector {
+            if previous.vector.get(node).unwrap_or(&0).checked_add(1) == Some(self_counter) {
+                ticked = true;
             }
         }
 
-        match (is_less, is_greater) {
-            (true, false) => Some(Ordering::Less),
-            (false, true) => Some(Ordering::Greater),
-            (false, false) => Some(Ordering::Equal),
-            _ => None,
-        }
+        ticked
     }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
-pub struct VClockTime(pub HashMap<Identifier, u64>);
 
-impl VClockTime {
-    /// Serializes the vector clock time to bytes
+    /// Merges another vector clock into this one, restricted to the nodes in
+    /// `allowed`
+    ///
+    /// In closed-membership systems a peer may carry entries for nodes that were
+    /// never registered locally. Those components are ignored rather than
+    /// silently added to this clock.
     ///
     /// # Examples
     ///
     /// ```
-    /// use logical_clocks_rs::VClock;
-    /// 
+    /// use logical_clocks_rs::{Identifier, VClock, Vector};
+    ///
+    /// let member = Identifier::new();
+    /// let stranger = Identifier::new();
+    ///
+    /// let mut members = Vector::new();
+    /// members.add(member.clone());
+    ///
+    /// let mut peer = VClock::new();
+    /// peer.increment(&member);
+    /// peer.increment(&stranger);
+    ///
     /// let mut vclock = VClock::new();
-    /// let bytes = vclock.time().to_bytes().unwrap();
+    /// vclock.merge_within(&peer, &members);
+    /// assert!(!vclock.time().0.contains_key(&stranger));
     /// ```
-    pub fn to_bytes(&self) -> Result<Vec<u8>, BincodeError> {
-        bincode::serialize(&self.0)
+    pub fn merge_within(&mut self, other: &VClock, allowed: &Vector) {
+        for (node, &counter) in &other.vector {
+            if allowed.data.contains_key(node) {
+                merge_entry(&mut self.vector, node.clone(), counter);
+            }
+        }
     }
 
-    /// Deserializes the vector clock time from bytes
+    /// Merges a snapshot encoded with `VClockTime::to_canonical_bytes` directly
+    /// from a byte slice
+    ///
+    /// Entries are parsed and max-merged one at a time, so peak memory stays
+    /// bounded even for very large (e.g. memory-mapped) snapshots. If the input
+    /// is malformed an error is returned; entries decoded before the error have
+    /// already been merged, which still leaves the clock in a valid state.
     ///
     /// # Examples
     ///
     /// ```
-    /// use logical_clocks_rs::{VClock, VClockTime};
-    /// 
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut peer = VClock::new();
+    /// peer.increment(&id);
+    ///
     /// let mut vclock = VClock::new();
-    /// let bytes = vclock.time().to_bytes().unwrap();
-    /// let time = VClockTime::from_bytes(&bytes);
+    /// vclock.merge_from_slice(&peer.time().to_canonical_bytes()).unwrap();
+    /// assert_eq!(vclock, peer);
     /// ```
-    pub fn from_bytes(data: &[u8]) -> Result<Self, BincodeError> {
-        let clock: HashMap<Identifier, u64> = bincode::deserialize(data)?;
-        Ok(VClockTime(clock))
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_vclock_increment() {
-        let id = Identifier::new();
-        let mut vclock = VClock::new();
-
-        vclock.increment(&id);
-        let current_time = vclock.time();
-        assert_eq!(*current_time.0.get(&id).unwrap(), 1);
-    }
-
-    #[test]
-    fn test_vclock_merge() {
-        let mut vclock1 = VClock::new();
-        let mut vclock2 = VClock::new();
+    pub fn merge_from_slice(&mut self, data: &[u8]) -> Result<(), ClockError> {
+        let (count, mut rest) = read_u32(data)?;
 
-        let id1 = Identifier::new();
-        let id2 = Identifier::new();
-       
-        vclock1.increment(&id1);
-        vclock2.increment(&id2);
-        vclock2.increment(&id2);
+        for _ in 0..count {
+            let (node, tail) = read_identifier(rest)?;
+            let (counter, tail) = read_u64(tail)?;
+            rest = tail;
 
-        vclock1.merge(&vclock2);
-        let current_time = vclock1.time();
+            merge_entry(&mut self.vector, node, counter);
+        }
 
-        assert_eq!(*current_time.0.get(&id1).unwrap(), 1);
-        assert_eq!(*current_time.0.get(&id2).unwrap(), 2);
+        if !rest.is_empty() {
+            return Err(ClockError::TrailingBytes);
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_vclock_serialization() {
-        let id = Identifier::new();
-        let mut vclock = VClock::new();
+    /// Checks if the event numbered `event_counter` from `sender` has already
+    /// been delivered, i.e. this clock's entry for `sender` is at least
+    /// `event_counter`
+    ///
+    /// This is the standard at-most-once check for causal broadcast.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let sender = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&sender);
+    ///
+    /// assert!(vclock.already_seen(&sender, 1));
+    /// assert!(!vclock.already_seen(&sender, 2));
+    /// ```
+    pub fn already_seen(&self, sender: &Identifier, event_counter: u64) -> bool {
+        *self.vector.get(sender).unwrap_or(&0) >= event_counter
+    }
 
-        vclock.increment(&id);
-        let time = vclock.time();
+    /// Returns the total number of events recorded across all nodes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&Identifier::new());
+    /// vclock.increment(&Identifier::new());
+    /// assert_eq!(vclock.total_events(), 2);
+    /// ```
+    pub fn total_events(&self) -> u64 {
+        self.vector.values().sum()
+    }
 
-        let serialized = time.to_bytes();
-        assert!(serialized.is_ok());
+    /// Enumerates the events implied by this clock as `(node, k)` pairs, with
+    /// `k` running from 1 to the node's counter
+    ///
+    /// Events of a single node are yielded in order; the order across nodes is
+    /// unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&id);
+    /// vclock.increment(&id);
+    ///
+    /// let events: Vec<_> = vclock.event_stream().collect();
+    /// assert_eq!(events, vec![(id.clone(), 1), (id, 2)]);
+    /// ```
+    pub fn event_stream(&self) -> impl Iterator<Item = (Identifier, u64)> + '_ {
+        self.vector
+            .iter()
+            .flat_map(|(node, &counter)| (1..=counter).map(move |k| (node.clone(), k)))
+    }
 
-        let deserialized = VClockTime::from_bytes(&serialized.unwrap());
-        
-        assert!(deserialized.is_ok());
-        assert_eq!(time, deserialized.unwrap());
+    /// Estimates how many events a peer at `peer` is missing from this clock,
+    /// i.e. the sum over all nodes of how far this clock is ahead
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&id);
+    /// vclock.increment(&id);
+    ///
+    /// assert_eq!(vclock.transfer_estimate(&VClock::new().time()), 2);
+    /// ```
+    pub fn transfer_estimate(&self, peer: &VClockTime) -> u64 {
+        self.vector
+            .iter()
+            .map(|(node, &counter)| counter.saturating_sub(*peer.0.get(node).unwrap_or(&0)))
+            .sum()
     }
 
-    #[test]
-    fn test_vclock_equality() {
-        let mut vclock1 = VClock::new();
-        let mut vclock2 = VClock::new();
+    /// Records a local event: increments the node and returns the resulting
+    /// snapshot to tag the event with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// let stamp = vclock.record_local(&id);
+    /// assert_eq!(stamp, vclock.time());
+    /// ```
+    pub fn record_local(&mut self, id: &Identifier) -> VClockTime {
+        self.increment(id);
+        self.time()
+    }
 
-        let id1 = Identifier::new();
-        let id2 = Identifier::new();
+    /// Decomposes two clocks into their common base and the parts each one is
+    /// ahead by, as needed by a three-way merge
+    ///
+    /// Returns `(base, self_ahead, other_ahead)` where `base` is the
+    /// component-wise minimum of the two clocks and each `*_ahead` clock holds
+    /// the per-node difference from the base (zero differences are omitted).
+    /// Adding a clock's ahead part back onto the base reproduces it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut ours = VClock::new();
+    /// ours.increment(&id);
+    /// let theirs = ours.clone();
+    /// ours.increment(&id);
+    ///
+    /// let (base, ours_ahead, theirs_ahead) = ours.merge_base(&theirs);
+    /// assert_eq!(base, theirs);
+    /// assert_eq!(ours_ahead.total_events(), 1);
+    /// assert_eq!(theirs_ahead, VClock::new());
+    /// ```
+    pub fn merge_base(&self, other: &VClock) -> (VClock, VClock, VClock) {
+        let mut base = VClock::new();
+        let mut self_ahead = VClock::new();
+        let mut other_ahead = VClock::new();
 
-        vclock1.increment(&id1);
-        vclock1.increment(&id1);
-        vclock1.increment(&id1);
-        vclock2.increment(&id2);
+        let nodes: HashSet<_> = self.vector.keys().chain(other.vector.keys()).collect();
+        for node in nodes {
+            let self_counter = *self.vector.get(node).unwrap_or(&0);
+            let other_counter = *other.vector.get(node).unwrap_or(&0);
+            let min = self_counter.min(other_counter);
 
-        vclock1.merge(&vclock2);
-        vclock2.merge(&vclock1);
+            if min > 0 {
+                base.vector.insert(node.clone(), min);
+            }
+            if self_counter > min {
+                self_ahead.vector.insert(node.clone(), self_counter - min);
+            }
+            if other_counter > min {
+                other_ahead.vector.insert(node.clone(), other_counter - min);
+            }
+        }
 
-        assert_eq!(vclock1.time(), vclock2.time());
+        (base, self_ahead, other_ahead)
     }
 
-    #[test]
-    fn test_vclock_happened_before() {
-        let mut vclock1 = VClock::new();
-        let mut vclock2 = VClock::new();
-
-        let id1 = Identifier::new();
-        let id2 = Identifier::new();
-
-        vclock1.increment(&id1);
-        vclock1.increment(&id2);
-
-        vclock2.increment(&id1);
-        vclock2.increment(&id2);
-        vclock2.increment(&id2);
+    /// Merges another vector clock into this one, clamping each of its
+    /// counters to the replication cut
+    ///
+    /// For every node this merges `min(other_counter, cut_counter)`, so merging
+    /// never advances the clock past the cut (nodes absent from the cut count
+    /// as 0). Counters this clock already had beyond the cut are kept, and
+    /// nodes the cut excludes are not added to the clock at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut cut = VClock::new();
+    /// cut.increment(&id);
+    /// let mut peer = cut.clone();
+    /// peer.increment(&id);
+    ///
+    /// let mut vclock = VClock::new();
+    /// vclock.merge_up_to(&peer, &cut.time());
+    /// assert_eq!(vclock, cut);
+    /// ```
+    pub fn merge_up_to(&mut self, other: &VClock, cut: &VClockTime) {
+        for (node, &counter) in &other.vector {
+            let clamped = counter.min(*cut.0.get(node).unwrap_or(&0));
+            if clamped > *self.vector.get(node).unwrap_or(&0) {
+                self.vector.insert(node.clone(), clamped);
+            }
+        }
+    }
 
-        assert!(vclock1.happened_before(&vclock2));
-        assert!(!vclock2.happened_before(&vclock1));
+    /// Increments the logical clock for `id` only if it is one of `members`
+    ///
+    /// In closed-membership deployments an event under an unregistered
+    /// identifier is almost always a bug, so this fails with
+    /// `ClockError::UnknownNode` instead of silently adding the node. Use
+    /// `increment` for open membership.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{ClockError, Identifier, VClock, Vector};
+    ///
+    /// let member = Identifier::new();
+    /// let mut members = Vector::new();
+    /// members.add(member.clone());
+    ///
+    /// let mut vclock = VClock::with_vector(members.clone());
+    /// assert!(vclock.increment_checked(&member, &members).is_ok());
+    /// assert_eq!(
+    ///     vclock.increment_checked(&Identifier::new(), &members),
+    ///     Err(ClockError::UnknownNode)
+    /// );
+    /// ```
+    pub fn increment_checked(&mut self, id: &Identifier, members: &Vector) -> Result<(), ClockError> {
+        if !members.data.contains_key(id) {
+            return Err(ClockError::UnknownNode);
+        }
+        self.increment(id);
+        Ok(())
     }
 
-    #[test]
-    fn test_vclock_with_vector() {
-        let mut vector = Vector::new();
-        let id1 = Identifier::new();
-        let id2 = Identifier::new();
+    /// Returns the node with the highest counter, breaking ties in favour of
+    /// the smallest identifier, or `None` if no node has recorded an event
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let busy = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&busy);
+    /// vclock.increment(&busy);
+    /// vclock.increment(&Identifier::new());
+    ///
+    /// assert_eq!(vclock.dominant_node(), Some((busy, 2)));
+    /// ```
+    pub fn dominant_node(&self) -> Option<(Identifier, u64)> {
+        self.vector
+            .iter()
+            .filter(|(_, &counter)| counter > 0)
+            .max_by(|(a_node, a_counter), (b_node, b_counter)| {
+                a_counter.cmp(b_counter).then_with(|| b_node.cmp(a_node))
+            })
+            .map(|(node, &counter)| (node.clone(), counter))
+    }
+
+    /// Returns the direct dependencies of `event` as seen by this (receiving)
+    /// clock: for every node other than the event's origin, the counter value
+    /// that must already be present before the event can be delivered, sorted
+    /// by identifier
+    ///
+    /// The origin is the single node on which `event` is ahead of this clock.
+    /// If `event` is ahead on several nodes the origin cannot be told apart
+    /// from missing dependencies, so every non-zero entry is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let (a, b) = (Identifier::from_name("a"), Identifier::from_name("b"));
+    /// let mut local = VClock::new();
+    /// local.increment(&a);
+    ///
+    /// let mut sender = local.clone();
+    /// sender.increment(&b);
+    ///
+    /// assert_eq!(local.dependencies(&sender.time()), vec![(a, 1)]);
+    /// ```
+    pub fn dependencies(&self, event: &VClockTime) -> Vec<(Identifier, u64)> {
+        let mut ahead = event
+            .0
+            .iter()
+            .filter(|(node, &counter)| counter > *self.vector.get(*node).unwrap_or(&0));
+        let origin = match (ahead.next(), ahead.next()) {
+            (Some((node, _)), None) => Some(node),
+            _ => None,
+        };
+        let mut dependencies: Vec<(Identifier, u64)> = event
+            .0
+            .iter()
+            .filter(|(node, &counter)| counter > 0 && Some(*node) != origin)
+            .map(|(node, &counter)| (node.clone(), counter))
+            .collect();
+        dependencies.sort();
+        dependencies
+    }
+
+    /// Checks whether a message stamped `msg` by `from` can be delivered
+    /// under causal delivery
+    ///
+    /// The message must be the next event from `from` (its counter is exactly
+    /// one past ours) and must not depend on any event from another node that
+    /// has not been delivered yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let sender = Identifier::new();
+    /// let mut remote = VClock::new();
+    /// remote.increment(&sender);
+    /// let first = remote.time();
+    /// remote.increment(&sender);
+    /// let second = remote.time();
+    ///
+    /// let local = VClock::new();
+    /// assert!(local.can_deliver(&first, &sender));
+    /// assert!(!local.can_deliver(&second, &sender));
+    /// ```
+    pub fn can_deliver(&self, msg: &VClockTime, from: &Identifier) -> bool {
+        let local = |node: &Identifier| *self.vector.get(node).unwrap_or(&0);
+        msg.0.get(from).copied().unwrap_or(0) == local(from) + 1
+            && msg
+                .0
+                .iter()
+                .all(|(node, &counter)| node == from || counter <= local(node))
+    }
+
+    /// Checks whether this clock is behind `target` by at most `tolerance`
+    /// events on every node, i.e. `self_counter + tolerance >= target_counter`
+    ///
+    /// This supports bounded-staleness read routing: a replica within
+    /// tolerance of the client's last observed clock is close enough to serve
+    /// the read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let node = Identifier::new();
+    /// let replica = VClock::new();
+    /// let mut target = VClock::new();
+    /// target.increment(&node);
+    /// target.increment(&node);
+    ///
+    /// assert!(replica.within_tolerance(&target.time(), 2));
+    /// assert!(!replica.within_tolerance(&target.time(), 1));
+    /// ```
+    pub fn within_tolerance(&self, target: &VClockTime, tolerance: u64) -> bool {
+        target.0.iter().all(|(node, &counter)| {
+            self.vector.get(node).unwrap_or(&0).saturating_add(tolerance) >= counter
+        })
+    }
+
+    /// Checks whether this clock and `other` are concurrent, i.e. neither
+    /// happened before the other and they are not equal
+    ///
+    /// This is a single pass over the union of nodes, returning as soon as
+    /// each clock has been seen ahead of the other on some node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let mut vclock1 = VClock::new();
+    /// vclock1.increment(&Identifier::new());
+    /// let mut vclock2 = VClock::new();
+    /// vclock2.increment(&Identifier::new());
+    ///
+    /// assert!(vclock1.concurrent_with(&vclock2));
+    /// assert!(!vclock1.concurrent_with(&vclock1));
+    /// ```
+    pub fn concurrent_with(&self, other: &VClock) -> bool {
+        compare_entries(&self.vector, &other.vector).is_none()
+    }
+
+    /// Removes every entry whose counter is 0
+    ///
+    /// A missing entry already compares equal to a zero-valued one, so this
+    /// never changes the result of a comparison or merge; it only shrinks the
+    /// clock, e.g. after `with_vector` seeded many nodes at 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock, Vector};
+    ///
+    /// let mut vector = Vector::new();
+    /// vector.add(Identifier::new()).add(Identifier::new());
+    /// let mut vclock = VClock::with_vector(vector);
+    ///
+    /// vclock.prune_zeros();
+    /// assert!(vclock.time().0.is_empty());
+    /// assert_eq!(vclock, VClock::new());
+    /// ```
+    pub fn prune_zeros(&mut self) {
+        self.vector.retain(|_, counter| *counter > 0);
+    }
+
+    /// Returns the counter for `node`, or 0 if the clock has no entry for it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// assert_eq!(vclock.get(&id), 0);
+    /// vclock.increment(&id);
+    /// assert_eq!(vclock.get(&id), 1);
+    /// ```
+    pub fn get(&self, node: &Identifier) -> u64 {
+        *self.vector.get(node).unwrap_or(&0)
+    }
+
+    /// Checks whether the clock has recorded any event from `node`
+    ///
+    /// A zero-valued entry is treated like a missing one, consistent with
+    /// equality and comparison, so this is the same as `get(node) > 0`.
+    pub fn contains(&self, node: &Identifier) -> bool {
+        self.get(node) > 0
+    }
+
+    /// Returns an iterator over the `(node, counter)` entries of the clock
+    ///
+    /// Iteration borrows the clock and does not allocate. The order is
+    /// unspecified because the clock is backed by a `HashMap`. Zero-valued
+    /// entries, e.g. from `with_vector`, are yielded as stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&id);
+    ///
+    /// let entries: Vec<_> = vclock.iter().collect();
+    /// assert_eq!(entries, vec![(&id, 1)]);
+    /// ```
+    pub fn iter(&self) -> VClockIter<'_> {
+        VClockIter { inner: self.vector.iter() }
+    }
+
+    /// Merges every clock in `others` into this one, taking the component-wise
+    /// maximum of all of them
+    ///
+    /// The result is identical to calling `merge` for each clock in turn, but
+    /// capacity for the largest input is reserved up front so the map grows at
+    /// most once for nodes all peers share.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let (a, b) = (Identifier::new(), Identifier::new());
+    /// let mut peer1 = VClock::new();
+    /// peer1.increment(&a);
+    /// let mut peer2 = VClock::new();
+    /// peer2.increment(&b);
+    ///
+    /// let mut vclock = VClock::new();
+    /// vclock.merge_many(&[peer1, peer2]);
+    /// assert_eq!((vclock.get(&a), vclock.get(&b)), (1, 1));
+    /// ```
+    pub fn merge_many(&mut self, others: &[VClock]) {
+        let largest = others.iter().map(|other| other.vector.len()).max().unwrap_or(0);
+        self.vector.reserve(largest.saturating_sub(self.vector.len()));
+
+        for other in others {
+            merge_entries(&mut self.vector, &other.vector);
+        }
+    }
+
+    /// Returns the join of two clocks, the component-wise maximum, as a new clock
+    ///
+    /// This is `merge` without mutating either input; `&a | &b` is shorthand for it.
+    /// The result descends from both `self` and `other` and is the smallest clock
+    /// that does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let (a, b) = (Identifier::new(), Identifier::new());
+    /// let mut left = VClock::new();
+    /// let mut right = VClock::new();
+    /// left.increment(&a);
+    /// right.increment(&b);
+    ///
+    /// let joined = left.join(&right);
+    /// assert!(joined.descends(&left) && joined.descends(&right));
+    /// assert_eq!(joined, &left | &right);
+    /// ```
+    pub fn join(&self, other: &VClock) -> VClock {
+        let mut joined = self.clone();
+        joined.merge(other);
+        joined
+    }
+
+    /// Checks whether this clock is at least as up to date as `other`, i.e.
+    /// every entry of `other` is `<=` the corresponding entry of this clock
+    ///
+    /// Unlike `happened_before` this is not strict: equal clocks descend from
+    /// each other. `a.happened_before(&b)` implies `b.descends(&a)`, but not
+    /// the reverse. An update whose clock this clock descends from has already
+    /// been seen and can be safely dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let mut a = VClock::new();
+    /// a.increment(&Identifier::new());
+    /// let b = a.clone();
+    ///
+    /// assert!(a.descends(&b) && b.descends(&a));
+    /// assert!(a.descends(&VClock::new()));
+    /// assert!(!VClock::new().descends(&a));
+    /// ```
+    pub fn descends(&self, other: &VClock) -> bool {
+        other
+            .vector
+            .iter()
+            .all(|(node, &counter)| counter <= *self.vector.get(node).unwrap_or(&0))
+    }
+
+    /// Checks whether this clock strictly dominates `other`: it descends from
+    /// `other` and they are not equal
+    ///
+    /// This is the same as `other.happened_before(self)`.
+    pub fn dominates(&self, other: &VClock) -> bool {
+        self.descends(other) && !other.descends(self)
+    }
+
+    /// Removes the entry for `node`, e.g. when a node id is permanently
+    /// retired
+    ///
+    /// Since a missing entry compares as zero, the node afterwards behaves as
+    /// if it had never been seen; the only difference from a zero-valued
+    /// entry is the smaller serialized size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let retired = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&retired);
+    ///
+    /// vclock.clear_node(&retired);
+    /// assert_eq!(vclock, VClock::new());
+    /// ```
+    pub fn clear_node(&mut self, node: &Identifier) {
+        self.vector.remove(node);
+    }
+
+    /// Removes every entry, returning the clock to its initial state
+    pub fn reset(&mut self) {
+        self.vector.clear();
+    }
+
+    /// Returns only the entries that advanced past what a peer already knows
+    ///
+    /// The result holds every node whose counter in `self` is greater than its
+    /// counter in `known` (a missing entry counts as 0). Shipping the delta
+    /// instead of the full clock and applying it with `apply_delta` gives the
+    /// same result as a full `merge`, provided the peer's clock descends from
+    /// `known`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let (a, b) = (Identifier::new(), Identifier::new());
+    /// let mut local = VClock::new();
+    /// local.increment(&a);
+    /// local.increment(&b);
+    /// let mut remote = local.clone();
+    ///
+    /// local.increment(&a);
+    /// let delta = local.delta_since(&remote.time());
+    /// assert_eq!(delta.0.len(), 1);
+    ///
+    /// remote.apply_delta(&delta);
+    /// assert_eq!(remote, local);
+    /// ```
+    pub fn delta_since(&self, known: &VClockTime) -> VClockTime {
+        VClockTime(
+            self.vector
+                .iter()
+                .filter(|(node, &counter)| counter > known.0.get(*node).copied().unwrap_or(0))
+                .map(|(node, &counter)| (node.clone(), counter))
+                .collect(),
+        )
+    }
+
+    /// Merges a delta produced by `delta_since` into this clock, keeping the
+    /// maximum counter for each node
+    pub fn apply_delta(&mut self, delta: &VClockTime) {
+        merge_entries(&mut self.vector, &delta.0);
+    }
+}
+
+/// Iterator over the `(node, counter)` entries of a `VClock`, created by
+/// `VClock::iter`
+#[derive(Debug, Clone)]
+pub struct VClockIter<'a> {
+    inner: hash_map::Iter<'a, Identifier, u64>,
+}
+
+impl<'a> Iterator for VClockIter<'a> {
+    type Item = (&'a Identifier, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(node, &counter)| (node, counter))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for VClockIter<'_> {}
+
+impl<'a> IntoIterator for &'a VClock {
+    type Item = (&'a Identifier, u64);
+    type IntoIter = VClockIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl PartialEq for VClock {
+    fn eq(&self, other: &Self) -> bool {
+        entries_equal(&self.vector, &other.vector)
+    }
+}
+
+impl Eq for VClock {}
+
+/// Orders clocks by happened-before, with the same semantics as `VClockTime`:
+/// `a < b` exactly when `a.happened_before(&b)`, and concurrent clocks are
+/// incomparable
+impl PartialOrd for VClock {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        compare_entries(&self.vector, &other.vector)
+    }
+}
+
+impl BitOr for &VClock {
+    type Output = VClock;
+
+    fn bitor(self, other: &VClock) -> VClock {
+        self.join(other)
+    }
+}
+
+impl Default for VClock {
+    fn default() -> Self {
+        VClock::new()
+    }
+}
+
+impl PartialEq for VClockTime {
+    fn eq(&self, other: &Self) -> bool {
+        entries_equal(&self.0, &other.0)
+    }
+}
+
+impl Eq for VClockTime {}
+
+/// Hashes `canonical_hash`, which ignores zero-valued entries just as equality
+/// does and needs no allocation
+impl Hash for VClockTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.canonical_hash());
+    }
+}
+
+impl PartialOrd for VClockTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        compare_entries(&self.0, &other.0)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VClockTime(pub HashMap<Identifier, u64>);
+
+impl VClockTime {
+    /// Serializes the vector clock time to bytes
+    ///
+    /// The format is bincode with a pinned configuration: a little-endian
+    /// `u64` entry count followed by, for each entry, the identifier as a
+    /// little-endian `u64` length and its bytes, then the little-endian `u64`
+    /// counter. Entries are in map iteration order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::VClock;
+    /// 
+    /// let mut vclock = VClock::new();
+    /// let bytes = vclock.time().to_bytes().unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BincodeError> {
+        bincode_options().serialize(&self.0)
+    }
+
+    /// Deserializes the vector clock time from bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{VClock, VClockTime};
+    /// 
+    /// let mut vclock = VClock::new();
+    /// let bytes = vclock.time().to_bytes().unwrap();
+    /// let time = VClockTime::from_bytes(&bytes);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_bytes(data: &[u8]) -> Result<Self, BincodeError> {
+        let clock: HashMap<Identifier, u64> = bincode_options().deserialize(data)?;
+        Ok(VClockTime(clock))
+    }
+
+    /// Returns a 64-bit Bloom-style fingerprint of the nodes in this snapshot
+    /// and how far their counters have advanced
+    ///
+    /// Every node sets one bit for each of the counter thresholds 1, 16, 256
+    /// and 4096 it has reached, chosen by an FNV-1a hash of its identifier and
+    /// the threshold. Raising a counter only ever adds bits, which is what lets
+    /// `might_dominate` rule snapshots out. The hash is stable, so fingerprints
+    /// can be persisted alongside the snapshots they summarize.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let mut vclock = VClock::new();
+    /// assert_eq!(vclock.time().fingerprint(), 0);
+    /// vclock.increment(&Identifier::new());
+    /// assert_ne!(vclock.time().fingerprint(), 0);
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut bits = 0;
+        for (node, &counter) in &self.0 {
+            for (level, _) in FINGERPRINT_THRESHOLDS
+                .iter()
+                .enumerate()
+                .take_while(|(_, &threshold)| counter >= threshold)
+            {
+                let mut hasher = Fnv1aHasher::new();
+                hasher.write(&node.0);
+                hasher.write(&[level as u8]);
+                bits |= 1 << (hasher.finish() % 64);
+            }
+        }
+        bits
+    }
+
+    /// Cheaply checks whether this snapshot might dominate `other`
+    ///
+    /// A snapshot can only dominate `other` if every node has reached at least
+    /// the same counter thresholds, so a bit set in `other`'s fingerprint but
+    /// missing from ours rules domination out. A `false` result is definitive; a `true` result
+    /// may be a false positive and must be confirmed with a full comparison.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let older = VClock::new();
+    /// let mut newer = VClock::new();
+    /// newer.increment(&id);
+    ///
+    /// assert!(newer.time().might_dominate(&older.time()));
+    /// assert!(!older.time().might_dominate(&newer.time()));
+    /// ```
+    pub fn might_dominate(&self, other: &VClockTime) -> bool {
+        other.fingerprint() & !self.fingerprint() == 0
+    }
+
+    /// Serializes the vector clock time to a canonical byte format
+    ///
+    /// Entries are sorted by identifier, so equal snapshots always produce equal
+    /// bytes. The layout is a big-endian `u32` entry count followed by, for each
+    /// entry, a `u32` identifier length, the identifier bytes and a `u64`
+    /// counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::VClock;
+    ///
+    /// let vclock = VClock::new();
+    /// assert_eq!(vclock.time().to_canonical_bytes(), vec![0, 0, 0, 0]);
+    /// ```
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for (node, counter) in entries {
+            write_identifier(&mut bytes, node);
+            bytes.extend_from_slice(&counter.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Computes an order-independent hash of the snapshot from scratch
+    ///
+    /// Each non-zero entry is hashed on its own and the results are combined
+    /// with wrapping addition, so the value does not depend on map iteration
+    /// order and can be maintained incrementally by `HashedVClock`. Entries are
+    /// hashed with FNV-1a over fixed-endian bytes, so the value is stable across
+    /// processes, platforms and releases. This is the hash behind `VClockTime`'s
+    /// `Hash` impl.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{HashedVClock, Identifier};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = HashedVClock::new();
+    /// vclock.increment(&id);
+    /// assert_eq!(vclock.rolling_hash(), vclock.time().canonical_hash());
+    /// ```
+    pub fn canonical_hash(&self) -> u64 {
+        self.0
+            .iter()
+            .fold(0u64, |acc, (node, &counter)| acc.wrapping_add(entry_hash(node, counter)))
+    }
+
+    /// Computes a deterministic hash of the snapshot that is stable across
+    /// processes, platforms and releases
+    ///
+    /// The non-zero entries are sorted by identifier and fed, as length-prefixed
+    /// identifier bytes and big-endian counters, to a 64-bit FNV-1a hash. Equal
+    /// snapshots therefore hash equally whatever their map iteration order, and
+    /// the value is safe to persist. It allocates and sorts on every call, so
+    /// in-memory hashing uses `canonical_hash` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&Identifier::new());
+    /// assert_eq!(vclock.time().stable_hash(), vclock.clone().time().stable_hash());
+    /// ```
+    pub fn stable_hash(&self) -> u64 {
+        let mut entries: Vec<_> = self.0.iter().filter(|(_, &counter)| counter > 0).collect();
+        entries.sort();
+
+        let mut hasher = Fnv1aHasher::new();
+        for (node, counter) in entries {
+            hasher.write(&(node.0.len() as u64).to_be_bytes());
+            hasher.write(&node.0);
+            hasher.write(&counter.to_be_bytes());
+        }
+        hasher.finish()
+    }
+
+    /// Encodes only the entries that differ from `baseline`
+    ///
+    /// Consecutive snapshots in a log usually differ in a handful of nodes, so
+    /// storing each one as a delta against its predecessor is far smaller than
+    /// a full encoding. The layout is a big-endian `u32` entry count followed by,
+    /// for each entry sorted by identifier, a tag byte (`0` = set, `1` =
+    /// removed), the length-prefixed identifier and, for set entries, a `u64`
+    /// counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock, VClockTime};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&id);
+    /// let baseline = vclock.time();
+    /// vclock.increment(&id);
+    ///
+    /// let delta = vclock.time().delta_encode(&baseline);
+    /// assert_eq!(VClockTime::delta_decode(&baseline, &delta).unwrap(), vclock.time());
+    /// ```
+    pub fn delta_encode(&self, baseline: &VClockTime) -> Vec<u8> {
+        let mut entries: Vec<(&Identifier, Option<u64>)> = self
+            .0
+            .iter()
+            .filter(|(node, counter)| baseline.0.get(*node) != Some(counter))
+            .map(|(node, &counter)| (node, Some(counter)))
+            .chain(
+                baseline
+                    .0
+                    .keys()
+                    .filter(|node| !self.0.contains_key(*node))
+                    .map(|node| (node, None)),
+            )
+            .collect();
+        entries.sort();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for (node, counter) in entries {
+            bytes.push(if counter.is_some() { DELTA_SET } else { DELTA_REMOVE });
+            write_identifier(&mut bytes, node);
+            if let Some(counter) = counter {
+                bytes.extend_from_slice(&counter.to_be_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Reconstructs a snapshot from `baseline` and a delta produced by
+    /// `delta_encode`
+    pub fn delta_decode(baseline: &VClockTime, data: &[u8]) -> Result<VClockTime, ClockError> {
+        let mut clock = baseline.0.clone();
+        let (count, mut rest) = read_u32(data)?;
+
+        for _ in 0..count {
+            let (&tag, tail) = rest.split_first().ok_or(ClockError::Truncated)?;
+            let (node, tail) = read_identifier(tail)?;
+            rest = match tag {
+                DELTA_SET => {
+                    let (counter, tail) = read_u64(tail)?;
+                    clock.insert(node, counter);
+                    tail
+                }
+                DELTA_REMOVE => {
+                    clock.remove(&node);
+                    tail
+                }
+                tag => return Err(ClockError::InvalidTag(tag)),
+            };
+        }
+
+        if !rest.is_empty() {
+            return Err(ClockError::TrailingBytes);
+        }
+        Ok(VClockTime(clock))
+    }
+
+    /// Serializes the vector clock time to a fixed layout that is trivial to
+    /// parse without bincode
+    ///
+    /// The layout is a big-endian `u32` entry count followed by, for each entry
+    /// sorted by identifier, the 16 identifier bytes and a big-endian `u64`
+    /// counter. Fails with `ClockError::InvalidIdentifierLength` if any
+    /// identifier is not exactly 16 bytes long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock, VClockTime};
+    ///
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&Identifier::new());
+    ///
+    /// let bytes = vclock.time().to_bytes_fixed().unwrap();
+    /// assert_eq!(bytes.len(), 4 + 24);
+    /// assert_eq!(VClockTime::from_bytes_fixed(&bytes).unwrap(), vclock.time());
+    /// ```
+    pub fn to_bytes_fixed(&self) -> Result<Vec<u8>, ClockError> {
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort();
+
+        let mut bytes = Vec::with_capacity(4 + entries.len() * 24);
+        bytes.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for (node, counter) in entries {
+            if node.0.len() != 16 {
+                return Err(ClockError::InvalidIdentifierLength(node.0.len()));
+            }
+            bytes.extend_from_slice(&node.0);
+            bytes.extend_from_slice(&counter.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    /// Deserializes the vector clock time from the layout produced by
+    /// `to_bytes_fixed`
+    pub fn from_bytes_fixed(data: &[u8]) -> Result<Self, ClockError> {
+        let (count, mut rest) = read_u32(data)?;
+        let mut clock = HashMap::new();
+
+        for _ in 0..count {
+            if rest.len() < 16 {
+                return Err(ClockError::Truncated);
+            }
+            let (id_bytes, tail) = rest.split_at(16);
+            let (counter, tail) = read_u64(tail)?;
+            clock.insert(Identifier::from_bytes(id_bytes.to_vec()), counter);
+            rest = tail;
+        }
+
+        if !rest.is_empty() {
+            return Err(ClockError::TrailingBytes);
+        }
+        Ok(VClockTime(clock))
+    }
+
+    /// Serializes the vector clock time against a roster shared by both sides,
+    /// omitting identifiers entirely
+    ///
+    /// The layout is a presence bitmap of `ceil(roster.len() / 8)` bytes, where
+    /// bit `i % 8` of byte `i / 8` is set if roster entry `i` has a non-zero
+    /// counter, followed by a big-endian `u64` counter for each present entry
+    /// in roster order. Fails with `ClockError::UnknownNode` if a node with a
+    /// non-zero counter is not in the roster.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock, VClockTime};
+    ///
+    /// let roster = Identifier::generate(3);
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&roster[1]);
+    ///
+    /// let bytes = vclock.time().encode_roster(&roster).unwrap();
+    /// assert_eq!(bytes.len(), 1 + 8);
+    /// assert_eq!(VClockTime::decode_roster(&roster, &bytes).unwrap(), vclock.time());
+    /// ```
+    pub fn encode_roster(&self, roster: &[Identifier]) -> Result<Vec<u8>, ClockError> {
+        let positions: HashMap<&Identifier, usize> =
+            roster.iter().enumerate().map(|(i, node)| (node, i)).collect();
+        let mut counters = vec![0; roster.len()];
+        for (node, &counter) in self.0.iter().filter(|(_, &counter)| counter > 0) {
+            let i = *positions.get(node).ok_or(ClockError::UnknownNode)?;
+            counters[i] = counter;
+        }
+
+        let mut bytes = vec![0u8; roster.len().div_ceil(8)];
+        for (i, &counter) in counters.iter().enumerate() {
+            if counter > 0 {
+                bytes[i / 8] |= 1 << (i % 8);
+            }
+        }
+        for counter in counters.into_iter().filter(|&counter| counter > 0) {
+            bytes.extend_from_slice(&counter.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    /// Deserializes the vector clock time from the layout produced by
+    /// `encode_roster` with the same roster
+    pub fn decode_roster(roster: &[Identifier], data: &[u8]) -> Result<Self, ClockError> {
+        let bitmap_len = roster.len().div_ceil(8);
+        if data.len() < bitmap_len {
+            return Err(ClockError::Truncated);
+        }
+        let (bitmap, mut rest) = data.split_at(bitmap_len);
+        let mut clock = HashMap::new();
+
+        for (i, node) in roster.iter().enumerate() {
+            if bitmap[i / 8] & (1 << (i % 8)) != 0 {
+                let (counter, tail) = read_u64(rest)?;
+                clock.insert(node.clone(), counter);
+                rest = tail;
+            }
+        }
+
+        if !rest.is_empty() {
+            return Err(ClockError::TrailingBytes);
+        }
+        Ok(VClockTime(clock))
+    }
+
+    /// Serializes the vector clock time to a JSON object mapping the string
+    /// form of each identifier (UUID or base64) to its counter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock, VClockTime};
+    ///
+    /// let id = Identifier::from_name("node-0");
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&id);
+    ///
+    /// let json = vclock.time().to_json();
+    /// assert_eq!(json, format!("{{\"{}\":1}}", id));
+    /// assert_eq!(VClockTime::from_json(&json).unwrap(), vclock.time());
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let object: serde_json::Map<String, serde_json::Value> = self
+            .0
+            .iter()
+            .map(|(node, &counter)| (node.to_string(), counter.into()))
+            .collect();
+        serde_json::Value::Object(object).to_string()
+    }
+
+    /// Deserializes the vector clock time from the JSON produced by `to_json`
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, ClockError> {
+        let object: HashMap<String, u64> =
+            serde_json::from_str(json).map_err(|err| ClockError::Serialization(err.to_string()))?;
+        object
+            .into_iter()
+            .map(|(node, counter)| Ok((node.parse()?, counter)))
+            .collect::<Result<_, ClockError>>()
+            .map(VClockTime)
+    }
+
+    /// Merges another snapshot into this one, keeping the maximum counter for
+    /// each node
+    ///
+    /// This mirrors `VClock::merge` on the serializable snapshot type, so
+    /// persisted snapshots can be folded together without rebuilding a clock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock};
+    ///
+    /// let (a, b) = (Identifier::new(), Identifier::new());
+    /// let mut left = VClock::new();
+    /// let mut right = VClock::new();
+    /// left.increment(&a);
+    /// right.increment(&b);
+    ///
+    /// let mut time = left.time();
+    /// time.merge(&right.time());
+    /// assert_eq!(time, left.join(&right).time());
+    /// ```
+    pub fn merge(&mut self, other: &VClockTime) {
+        merge_entries(&mut self.0, &other.0);
+    }
+
+    /// Returns the merge of two snapshots as a new snapshot, leaving both
+    /// unchanged
+    pub fn merged(&self, other: &VClockTime) -> VClockTime {
+        let mut merged = self.clone();
+        merged.merge(other);
+        merged
+    }
+}
+
+/// A set of snapshots kept as a causal antichain, i.e. no member happened
+/// before another
+///
+/// This is the sibling-management logic of a multi-version store: a new
+/// version replaces every version it descends from, is dropped if an existing
+/// version already covers it, and is kept alongside concurrent versions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VClockSet {
+    members: Vec<VClockTime>,
+}
+
+impl VClockSet {
+    /// Creates a new empty set
+    pub fn new() -> Self {
+        VClockSet::default()
+    }
+
+    /// Inserts a snapshot, returning `false` if it was dominated by (or equal
+    /// to) an existing member and therefore skipped
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock, VClockSet};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// vclock.increment(&id);
+    /// let old = vclock.time();
+    /// vclock.increment(&id);
+    ///
+    /// let mut set = VClockSet::new();
+    /// assert!(set.insert(old.clone()));
+    /// assert!(set.insert(vclock.time()));
+    /// assert!(!set.insert(old));
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn insert(&mut self, time: VClockTime) -> bool {
+        if self.members.iter().any(|member| time <= *member) {
+            return false;
+        }
+        self.members.retain(|member| *member > time || member.partial_cmp(&time).is_none());
+        self.members.push(time);
+        true
+    }
+
+    /// Returns the current members
+    pub fn members(&self) -> &[VClockTime] {
+        &self.members
+    }
+
+    /// Returns the number of members
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns true if the set has no members
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// A mutation recorded by `AuditedVClock`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operation {
+    /// The clock was incremented for the given node
+    Increment(Identifier),
+    /// Another clock was merged in
+    Merge(VClockTime),
+}
+
+/// A vector clock that records every mutation together with the resulting
+/// state, for compliance audit trails
+///
+/// The clock semantics are those of `VClock`; the wrapper only appends an
+/// `(Operation, VClockTime)` entry to its log after each `increment` and
+/// `merge`.
+#[derive(Debug, Clone, Default)]
+pub struct AuditedVClock {
+    clock: VClock,
+    log: Vec<(Operation, VClockTime)>,
+}
+
+impl AuditedVClock {
+    /// Creates a new empty audited vector clock
+    pub fn new() -> Self {
+        AuditedVClock::default()
+    }
+
+    /// Increments the logical clock for the current node and records it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{AuditedVClock, Identifier, Operation};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = AuditedVClock::new();
+    /// vclock.increment(&id);
+    ///
+    /// assert_eq!(vclock.audit_log()[0].0, Operation::Increment(id));
+    /// ```
+    pub fn increment(&mut self, node_id: &Identifier) {
+        self.clock.increment(node_id);
+        self.log.push((Operation::Increment(node_id.clone()), self.clock.time()));
+    }
+
+    /// Merges another vector clock into this one and records it
+    pub fn merge(&mut self, other: &VClock) {
+        self.clock.merge(other);
+        self.log.push((Operation::Merge(other.time()), self.clock.time()));
+    }
+
+    /// Returns every recorded operation with the state it produced, oldest
+    /// first
+    pub fn audit_log(&self) -> &[(Operation, VClockTime)] {
+        &self.log
+    }
+
+    /// Returns the current vector clock time
+    pub fn time(&self) -> VClockTime {
+        self.clock.time()
+    }
+
+    /// Returns the underlying vector clock
+    pub fn clock(&self) -> &VClock {
+        &self.clock
+    }
+}
+
+/// Values keyed by the vector clock time at which they were written
+///
+/// Entries are kept in insertion order and queries scan every entry, so a
+/// query costs one clock comparison per stored value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CausalMap<V> {
+    entries: Vec<(VClockTime, V)>,
+}
+
+impl<V> CausalMap<V> {
+    /// Creates a new empty map
+    pub fn new() -> Self {
+        CausalMap { entries: Vec::new() }
+    }
+
+    /// Stores `value` as written at `time`
+    pub fn insert(&mut self, time: VClockTime, value: V) {
+        self.entries.push((time, value));
+    }
+
+    /// Returns every value whose write time happened strictly before `x`, in
+    /// insertion order
+    ///
+    /// Values written at a time equal to or concurrent with `x` are excluded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{CausalMap, Identifier, VClock};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// let mut map = CausalMap::new();
+    ///
+    /// vclock.increment(&id);
+    /// map.insert(vclock.time(), "first");
+    /// vclock.increment(&id);
+    /// map.insert(vclock.time(), "second");
+    ///
+    /// assert_eq!(map.query_before(&vclock.time()), vec![&"first"]);
+    /// ```
+    pub fn query_before(&self, x: &VClockTime) -> Vec<&V> {
+        self.entries
+            .iter()
+            .filter(|(time, _)| time.partial_cmp(x) == Some(Ordering::Less))
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    /// Returns the number of stored values
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no values are stored
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<V> Default for CausalMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A chronological record of vector clock snapshots
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VClockHistory {
+    snapshots: Vec<VClockTime>,
+}
+
+impl VClockHistory {
+    /// Creates a new empty history
+    pub fn new() -> Self {
+        VClockHistory::default()
+    }
+
+    /// Appends a snapshot to the history
+    pub fn record(&mut self, time: VClockTime) {
+        self.snapshots.push(time);
+    }
+
+    /// Returns every recorded snapshot, oldest first
+    pub fn snapshots(&self) -> &[VClockTime] {
+        &self.snapshots
+    }
+
+    /// Returns the most recently recorded snapshot
+    pub fn latest(&self) -> Option<&VClockTime> {
+        self.snapshots.last()
+    }
+
+    /// Returns the number of recorded snapshots
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Returns true if no snapshots have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Returns the most recent snapshot that is `<=` `reference` in causal
+    /// order, i.e. the state a read tagged with `reference` observed
+    ///
+    /// If every recorded snapshot is later than or concurrent with the
+    /// reference there is no answer and `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{Identifier, VClock, VClockHistory};
+    ///
+    /// let id = Identifier::new();
+    /// let mut vclock = VClock::new();
+    /// let mut history = VClockHistory::new();
+    ///
+    /// vclock.increment(&id);
+    /// history.record(vclock.time());
+    /// let read = vclock.time();
+    /// vclock.increment(&id);
+    /// history.record(vclock.time());
+    ///
+    /// assert_eq!(history.snapshot_at_or_before(&read), Some(&read));
+    /// ```
+    pub fn snapshot_at_or_before(&self, reference: &VClockTime) -> Option<&VClockTime> {
+        self.snapshots.iter().rev().find(|snapshot| *snapshot <= reference)
+    }
+}
+
+/// A vector clock that keeps a rolling hash of its state up to date on every
+/// `increment` and `merge`
+///
+/// The rolling hash always equals `VClockTime::canonical_hash` of the current
+/// state, without rehashing every entry each time it is read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HashedVClock {
+    clock: VClock,
+    hash: u64,
+}
+
+impl HashedVClock {
+    /// Creates a new empty hashed vector clock
+    pub fn new() -> Self {
+        HashedVClock::default()
+    }
+
+    /// Wraps an existing vector clock, hashing its current state once
+    pub fn with_clock(clock: VClock) -> Self {
+        let hash = clock.time().canonical_hash();
+        HashedVClock { clock, hash }
+    }
+
+    /// Increments the logical clock for the current node, updating the hash
+    pub fn increment(&mut self, node_id: &Identifier) {
+        let entry = self.clock.vector.entry(node_id.clone()).or_insert(0);
+        self.hash = self
+            .hash
+            .wrapping_sub(entry_hash(node_id, *entry))
+            .wrapping_add(entry_hash(node_id, *entry + 1));
+        *entry += 1;
+    }
+
+    /// Merges another vector clock into this one, updating the hash for every
+    /// entry that changed
+    pub fn merge(&mut self, other: &VClock) {
+        for (node, &counter) in &other.vector {
+            if let Some(previous) = merge_entry(&mut self.clock.vector, node.clone(), counter) {
+                self.hash = self
+                    .hash
+                    .wrapping_sub(entry_hash(node, previous))
+                    .wrapping_add(entry_hash(node, counter));
+            }
+        }
+    }
+
+    /// Returns the incrementally maintained hash of the current state
+    pub fn rolling_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the current vector clock time
+    pub fn time(&self) -> VClockTime {
+        self.clock.time()
+    }
+
+    /// Returns the underlying vector clock
+    pub fn clock(&self) -> &VClock {
+        &self.clock
+    }
+}
+
+/// A vector clock that counts the merges that brought in genuinely
+/// concurrent state, for observability
+///
+/// A merge is a conflict when both clocks had advanced independently of each
+/// other, i.e. they were concurrent. Merging a clock that is behind or ahead of
+/// this one is sequential and never counted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictCountingVClock {
+    clock: VClock,
+    conflicts: u64,
+}
+
+impl ConflictCountingVClock {
+    /// Creates a new empty vector clock with no conflicts
+    pub fn new() -> Self {
+        ConflictCountingVClock::default()
+    }
+
+    /// Wraps an existing vector clock, starting the conflict count at zero
+    pub fn with_clock(clock: VClock) -> Self {
+        ConflictCountingVClock { clock, conflicts: 0 }
+    }
+
+    /// Increments the logical clock for the current node
+    pub fn increment(&mut self, node_id: &Identifier) {
+        self.clock.increment(node_id);
+    }
+
+    /// Merges another vector clock into this one, counting a conflict if the
+    /// two clocks were concurrent
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{ConflictCountingVClock, Identifier, VClock};
+    ///
+    /// let mut vclock = ConflictCountingVClock::new();
+    /// vclock.increment(&Identifier::new());
+    ///
+    /// let mut other = VClock::new();
+    /// other.increment(&Identifier::new());
+    /// vclock.merge(&other);
+    /// assert_eq!(vclock.conflict_count(), 1);
+    /// ```
+    pub fn merge(&mut self, other: &VClock) {
+        if self.clock.concurrent_with(other) {
+            self.conflicts += 1;
+        }
+        self.clock.merge(other);
+    }
+
+    /// Returns the number of concurrent merges over the clock's lifetime
+    pub fn conflict_count(&self) -> u64 {
+        self.conflicts
+    }
+
+    /// Returns the current vector clock time
+    pub fn time(&self) -> VClockTime {
+        self.clock.time()
+    }
+
+    /// Returns the underlying vector clock
+    pub fn clock(&self) -> &VClock {
+        &self.clock
+    }
+}
+
+/// A vector clock that keeps at most `capacity` entries, evicting the node with
+/// the lowest counter whenever an `increment` or `merge` would exceed it
+///
+/// Evicted entries are forgotten and the highest evicted counter is kept as the
+/// pruned watermark, so the clock stays bounded under high node churn. The
+/// price is accuracy: `compare` reports two clocks as concurrent whenever an
+/// entry one of them holds could be hidden behind the other's watermark, so
+/// pruning can turn ordered clocks into falsely concurrent ones, and a node
+/// pruned from one clock and never seen by the other is ignored altogether.
+/// Pick a capacity comfortably above the number of live nodes so that only
+/// long-idle nodes, which have the lowest counters, are evicted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "BoundedVClockParts")]
+pub struct BoundedVClock {
+    clock: VClock,
+    capacity: usize,
+    pruned: u64,
+}
+
+impl BoundedVClock {
+    /// Creates a new empty vector clock that keeps at most `capacity` nodes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logical_clocks_rs::{BoundedVClock, Identifier};
+    ///
+    /// let (a, b, c) = (Identifier::new(), Identifier::new(), Identifier::new());
+    /// let mut vclock = BoundedVClock::with_capacity(2);
+    /// vclock.increment(&a);
+    /// vclock.increment(&a);
+    /// vclock.increment(&b);
+    /// vclock.increment(&c);
+    ///
+    /// // `b` had the lowest counter and was evicted to make room for `c`
+    /// assert_eq!(vclock.time().0.len(), 2);
+    /// assert!(!vclock.clock().contains(&b));
+    /// assert_eq!(vclock.pruned_watermark(), 1);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        BoundedVClock::with_clock(VClock::new(), capacity)
+    }
+
+    /// Wraps an existing vector clock, evicting its lowest entries if it already
+    /// holds more than `capacity` nodes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_clock(clock: VClock, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than zero");
+        let mut bounded = BoundedVClock { clock, capacity, pruned: 0 };
+        bounded.evict(None);
+        bounded
+    }
+
+    /// Increments the logical clock for `node_id`, evicting another node if the
+    /// clock is full; the incremented node itself is never evicted
+    pub fn increment(&mut self, node_id: &Identifier) {
+        self.clock.increment(node_id);
+        self.evict(Some(node_id));
+    }
+
+    /// Merges another bounded clock into this one, keeping the higher of the two
+    /// watermarks and evicting the lowest entries beyond the capacity
+    pub fn merge(&mut self, other: &BoundedVClock) {
+        self.clock.merge(&other.clock);
+        self.pruned = self.pruned.max(other.pruned);
+        self.evict(None);
+    }
+
+    /// Compares two bounded clocks, returning `None` when they are concurrent or
+    /// when pruning makes their order uncertain
+    ///
+    /// An entry held by one clock but missing from the other is uncertain when
+    /// its counter does not exceed the other clock's watermark, since the other
+    /// clock may have pruned that node at any counter up to the watermark.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use logical_clocks_rs::{BoundedVClock, Identifier};
+    ///
+    /// let (a, b) = (Identifier::new(), Identifier::new());
+    /// let mut old = BoundedVClock::with_capacity(1);
+    /// old.increment(&a);
+    /// let mut new = old.clone();
+    /// assert_eq!(old.compare(&new), Some(Ordering::Equal));
+    ///
+    /// // Making room for `b` prunes `a`, so `new` can no longer prove it has seen `a`
+    /// new.increment(&b);
+    /// assert_eq!(old.compare(&new), None);
+    /// ```
+    pub fn compare(&self, other: &BoundedVClock) -> Option<Ordering> {
+        let mut ordering = Ordering::Equal;
+        for node in self.clock.vector.keys().chain(other.clock.vector.keys()) {
+            let ours = self.clock.vector.get(node).copied();
+            let theirs = other.clock.vector.get(node).copied();
+            let component = match (ours, theirs) {
+                (None, Some(theirs)) if theirs > 0 && theirs <= self.pruned => return None,
+                (Some(ours), None) if ours > 0 && ours <= other.pruned => return None,
+                _ => ours.unwrap_or(0).cmp(&theirs.unwrap_or(0)),
+            };
+            ordering = match (ordering, component) {
+                (Ordering::Equal, next) => next,
+                (current, Ordering::Equal) => current,
+                (current, next) if current == next => current,
+                _ => return None,
+            };
+        }
+        Some(ordering)
+    }
+
+    /// Returns the highest counter of any node evicted from this clock or from a
+    /// clock merged into it, or 0 if nothing was pruned
+    pub fn pruned_watermark(&self) -> u64 {
+        self.pruned
+    }
+
+    /// Returns the maximum number of nodes the clock keeps
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the current vector clock time
+    pub fn time(&self) -> VClockTime {
+        self.clock.time()
+    }
+
+    /// Returns the underlying vector clock
+    pub fn clock(&self) -> &VClock {
+        &self.clock
+    }
+
+    /// Evicts the lowest entries, other than `keep`, until the clock fits its
+    /// capacity. Ties are broken by identifier so eviction is deterministic.
+    fn evict(&mut self, keep: Option<&Identifier>) {
+        while self.clock.vector.len() > self.capacity {
+            let victim = self
+                .clock
+                .vector
+                .iter()
+                .filter(|(node, _)| Some(*node) != keep)
+                .min_by_key(|(node, &counter)| (counter, *node))
+                .map(|(node, &counter)| (node.clone(), counter));
+            let Some((node, counter)) = victim else { break };
+            self.clock.vector.remove(&node);
+            self.pruned = self.pruned.max(counter);
+        }
+    }
+}
+
+/// The serialized fields of a `BoundedVClock`, validated through `with_clock`
+/// so that a decoded clock has a nonzero capacity and fits within it
+#[derive(Deserialize)]
+struct BoundedVClockParts {
+    clock: VClock,
+    capacity: usize,
+    pruned: u64,
+}
+
+impl TryFrom<BoundedVClockParts> for BoundedVClock {
+    type Error = ClockError;
+
+    fn try_from(parts: BoundedVClockParts) -> Result<Self, Self::Error> {
+        if parts.capacity == 0 {
+            return Err(ClockError::Serialization("capacity must be greater than zero".into()));
+        }
+        let mut bounded = BoundedVClock::with_clock(parts.clock, parts.capacity);
+        bounded.pruned = bounded.pruned.max(parts.pruned);
+        Ok(bounded)
+    }
+}
+
+/// Hashes a single clock entry; zero counters hash to zero so that absent and
+/// zero-valued entries contribute the same to `canonical_hash`
+fn entry_hash(node: &Identifier, counter: u64) -> u64 {
+    if counter == 0 {
+        return 0;
+    }
+    let mut hasher = Fnv1aHasher::new();
+    hasher.write(&(node.0.len() as u64).to_be_bytes());
+    hasher.write(&node.0);
+    hasher.write(&counter.to_be_bytes());
+    hasher.finish()
+}
+
+/// Merges `other` into `into`, keeping the maximum counter for each node
+fn merge_entries(into: &mut HashMap<Identifier, u64>, other: &HashMap<Identifier, u64>) {
+    for (node, &counter) in other {
+        merge_entry(into, node.clone(), counter);
+    }
+}
+
+/// Raises the entry for `node` to `counter` if it is higher, returning the
+/// previous counter when the entry changed
+fn merge_entry(into: &mut HashMap<Identifier, u64>, node: Identifier, counter: u64) -> Option<u64> {
+    let entry = into.entry(node).or_insert(0);
+    if counter > *entry {
+        Some(core::mem::replace(entry, counter))
+    } else {
+        None
+    }
+}
+
+/// Compares two clock maps, treating missing entries as zero
+fn entries_equal(a: &HashMap<Identifier, u64>, b: &HashMap<Identifier, u64>) -> bool {
+    a.iter().all(|(node, &counter)| *b.get(node).unwrap_or(&0) == counter)
+        && b.iter().all(|(node, &counter)| *a.get(node).unwrap_or(&0) == counter)
+}
+
+/// Compares two clock maps by happened-before, treating missing entries as zero
+fn compare_entries(a: &HashMap<Identifier, u64>, b: &HashMap<Identifier, u64>) -> Option<Ordering> {
+    let mut is_less = false;
+    let mut is_greater = false;
+
+    let keys: HashSet<_> = a.keys().chain(b.keys()).collect();
+
+    for key in keys {
+        let a_counter = a.get(key).unwrap_or(&0);
+        let b_counter = b.get(key).unwrap_or(&0);
+
+        if a_counter < b_counter {
+            is_less = true;
+        }
+        if a_counter > b_counter {
+            is_greater = true;
+        }
+
+        if is_less && is_greater {
+            return None;
+        }
+    }
+
+    match (is_less, is_greater) {
+        (true, false) => Some(Ordering::Less),
+        (false, true) => Some(Ordering::Greater),
+        (false, false) => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+/// Counter thresholds that each set a separate `VClockTime::fingerprint` bit
+const FINGERPRINT_THRESHOLDS: [u64; 4] = [1, 16, 256, 4096];
+
+/// Delta entry tag: the node's counter is set to the encoded value
+const DELTA_SET: u8 = 0;
+/// Delta entry tag: the node is absent from the encoded snapshot
+const DELTA_REMOVE: u8 = 1;
+
+/// Returns the indices of the maximal events in `events`, i.e. those that did
+/// not happen before any other event in the set. Equal snapshots are all kept.
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::{frontier, Identifier, VClock};
+///
+/// let id1 = Identifier::new();
+/// let id2 = Identifier::new();
+///
+/// let mut a = VClock::new();
+/// a.increment(&id1);
+/// let mut b = a.clone();
+/// b.increment(&id1);
+/// let mut c = a.clone();
+/// c.increment(&id2);
+///
+/// assert_eq!(frontier(&[a.time(), b.time(), c.time()]), vec![1, 2]);
+/// ```
+pub fn frontier(events: &[VClockTime]) -> Vec<usize> {
+    (0..events.len())
+        .filter(|&i| {
+            !events
+                .iter()
+                .any(|other| events[i].partial_cmp(other) == Some(Ordering::Less))
+        })
+        .collect()
+}
+
+/// Returns the peak number of mutually concurrent events over a chronological
+/// sequence of event clocks
+///
+/// The events are fed one at a time into a `VClockSet`, whose members form
+/// the causal frontier of everything seen so far: an event replaces the
+/// events it descends from and joins the concurrent ones. The peak is the
+/// largest frontier reached, i.e. the widest antichain at any point.
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::{peak_concurrency, Identifier, VClock};
+///
+/// let (a, b) = (Identifier::new(), Identifier::new());
+/// let mut left = VClock::new();
+/// left.increment(&a);
+/// let mut right = VClock::new();
+/// right.increment(&b);
+/// let mut joined = left.clone();
+/// joined.merge(&right);
+///
+/// assert_eq!(peak_concurrency(&[left.time(), right.time(), joined.time()]), 2);
+/// ```
+pub fn peak_concurrency(events: &[VClockTime]) -> usize {
+    let mut window = VClockSet::new();
+    let mut peak = 0;
+    for event in events {
+        window.insert(event.clone());
+        peak = peak.max(window.len());
+    }
+    peak
+}
+
+/// Returns every pair of replicas whose reported clocks are concurrent, i.e.
+/// neither dominates the other, which signals divergent histories
+///
+/// Each pair is ordered `(smaller, larger)` by identifier and the pairs are
+/// sorted, so the result is deterministic. Replicas reporting equal clocks or
+/// clocks ordered by happened-before are not reported.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use logical_clocks_rs::{detect_split_brain, Identifier, VClock};
+///
+/// let (r1, r2) = (Identifier::from_name("r1"), Identifier::from_name("r2"));
+/// let mut a = VClock::new();
+/// a.increment(&r1);
+/// let mut b = VClock::new();
+/// b.increment(&r2);
+///
+/// let reports = HashMap::from([(r1.clone(), a.time()), (r2.clone(), b.time())]);
+/// assert_eq!(detect_split_brain(&reports).len(), 1);
+/// ```
+pub fn detect_split_brain(clocks: &HashMap<Identifier, VClockTime>) -> Vec<(Identifier, Identifier)> {
+    let mut replicas: Vec<_> = clocks.iter().collect();
+    replicas.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut pairs = Vec::new();
+    for (i, (a, a_time)) in replicas.iter().enumerate() {
+        for (b, b_time) in &replicas[i + 1..] {
+            if a_time.partial_cmp(b_time).is_none() {
+                pairs.push(((*a).clone(), (*b).clone()));
+            }
+        }
+    }
+    pairs
+}
+
+/// Returns the covering edges `(earlier, later)` of the happened-before
+/// relation over `times`, i.e. its transitive reduction: `earlier` happened
+/// before `later` with no other time in between
+pub(crate) fn covering_edges(times: &[&VClockTime]) -> Vec<(usize, usize)> {
+    let less = |i: usize, j: usize| times[i].partial_cmp(times[j]) == Some(Ordering::Less);
+    let n = times.len();
+
+    let mut edges = Vec::new();
+    for i in 0..n {
+        for j in 0..n {
+            if less(i, j) && !(0..n).any(|k| less(i, k) && less(k, j)) {
+                edges.push((i, j));
+            }
+        }
+    }
+    edges
+}
+
+/// Sorts messages into a valid causal delivery order
+///
+/// Every message is placed after all messages that happened before it.
+/// Concurrent messages keep their relative input order, so the sort is stable.
+///
+/// # Examples
+///
+/// ```
+/// use logical_clocks_rs::{causal_sort, Identifier, VClock};
+///
+/// let id = Identifier::new();
+/// let mut vclock = VClock::new();
+/// vclock.increment(&id);
+/// let first = vclock.time();
+/// vclock.increment(&id);
+/// let second = vclock.time();
+///
+/// let sorted = causal_sort(vec![("b", second), ("a", first)]);
+/// assert_eq!(sorted[0].0, "a");
+/// ```
+pub fn causal_sort<T>(msgs: Vec<(T, VClockTime)>) -> Vec<(T, VClockTime)> {
+    let n = msgs.len();
+    let mut successors = vec![Vec::new(); n];
+    let mut in_degree = vec![0usize; n];
+
+    for i in 0..n {
+        for j in 0..n {
+            if msgs[i].1.partial_cmp(&msgs[j].1) == Some(Ordering::Less) {
+                successors[i].push(j);
+                in_degree[j] += 1;
+            }
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = (0..n).filter(|&i| in_degree[i] == 0).collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some(i) = ready.pop_first() {
+        order.push(i);
+        for &j in &successors[i] {
+            in_degree[j] -= 1;
+            if in_degree[j] == 0 {
+                ready.insert(j);
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<(T, VClockTime)>> = msgs.into_iter().map(Some).collect();
+    order.into_iter().map(|i| slots[i].take().unwrap()).collect()
+}
+
+/// Folds a stream of snapshots into their running join (component-wise max)
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor::block_on;
+/// use futures::stream;
+/// use logical_clocks_rs::{fold_clocks, Identifier, VClock};
+///
+/// let mut vclock = VClock::new();
+/// vclock.increment(&Identifier::new());
+///
+/// let joined = block_on(fold_clocks(stream::iter(vec![vclock.time()])));
+/// assert_eq!(joined, vclock.time());
+/// ```
+#[cfg(feature = "async")]
+pub async fn fold_clocks<S: futures::Stream<Item = VClockTime>>(stream: S) -> VClockTime {
+    use futures::StreamExt;
+
+    stream
+        .fold(VClockTime(HashMap::new()), |mut joined, time| async move {
+            joined.merge(&time);
+            joined
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_increment() {
+        let id = Identifier::new();
+        let mut vclock = VClock::new();
+
+        vclock.increment(&id);
+        let current_time = vclock.time();
+        assert_eq!(*current_time.0.get(&id).unwrap(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge() {
+        let mut vclock1 = VClock::new();
+        let mut vclock2 = VClock::new();
+
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+       
+        vclock1.increment(&id1);
+        vclock2.increment(&id2);
+        vclock2.increment(&id2);
+
+        vclock1.merge(&vclock2);
+        let current_time = vclock1.time();
+
+        assert_eq!(*current_time.0.get(&id1).unwrap(), 1);
+        assert_eq!(*current_time.0.get(&id2).unwrap(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_serialization() {
+        let id = Identifier::new();
+        let mut vclock = VClock::new();
+
+        vclock.increment(&id);
+        let time = vclock.time();
+
+        let serialized = time.to_bytes();
+        assert!(serialized.is_ok());
+
+        let deserialized = VClockTime::from_bytes(&serialized.unwrap());
+        
+        assert!(deserialized.is_ok());
+        assert_eq!(time, deserialized.unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_equality() {
+        let mut vclock1 = VClock::new();
+        let mut vclock2 = VClock::new();
+
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        vclock1.increment(&id1);
+        vclock1.increment(&id1);
+        vclock1.increment(&id1);
+        vclock2.increment(&id2);
+
+        vclock1.merge(&vclock2);
+        vclock2.merge(&vclock1);
+
+        assert_eq!(vclock1.time(), vclock2.time());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_happened_before() {
+        let mut vclock1 = VClock::new();
+        let mut vclock2 = VClock::new();
+
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        vclock1.increment(&id1);
+        vclock1.increment(&id2);
+
+        vclock2.increment(&id1);
+        vclock2.increment(&id2);
+        vclock2.increment(&id2);
+
+        assert!(vclock1.happened_before(&vclock2));
+        assert!(!vclock2.happened_before(&vclock1));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_with_vector() {
+        let mut vector = Vector::new();
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
 
         vector.add(id1.clone()).add(id2.clone());
 
-        let vclock = VClock::with_vector(vector);
+        let vclock = VClock::with_vector(vector);
+        let current_time = vclock.time();
+
+        assert_eq!(*current_time.0.get(&id1).unwrap(), 0);
+        assert_eq!(*current_time.0.get(&id2).unwrap(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_is_valid_successor() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut previous = VClock::new();
+        previous.increment(&id1);
+
+        // A local increment is a legal transition
+        let mut next = previous.clone();
+        next.increment(&id1);
+        assert!(next.is_valid_successor(&previous));
+
+        // So is a receive, which merges a peer's counters and ticks locally
+        let mut peer = VClock::new();
+        peer.increment(&id2);
+        peer.increment(&id2);
+        let mut merged = next.clone();
+        merged.merge(&peer);
+        merged.increment(&id1);
+        assert!(merged.is_valid_successor(&next));
+
+        // A regression on any node is illegal
+        assert!(!previous.is_valid_successor(&next));
+
+        // As is a duplicated record
+        assert!(!next.is_valid_successor(&next.clone()));
+
+        // A node jumping ahead without a local tick is not a legal step
+        let mut jumped = previous.clone();
+        for _ in 0..49 {
+            jumped.increment(&id1);
+        }
+        assert!(!jumped.is_valid_successor(&previous));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_frontier() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::new();
+
+        // root -> left, root -> right, left -> tip
+        let mut root = VClock::new();
+        root.increment(&id1);
+
+        let mut left = root.clone();
+        left.increment(&id2);
+
+        let mut right = root.clone();
+        right.increment(&id3);
+
+        let mut tip = left.clone();
+        tip.increment(&id2);
+
+        let events = vec![root.time(), left.time(), right.time(), tip.time()];
+        assert_eq!(frontier(&events), vec![2, 3]);
+
+        // Merging the branches leaves a single maximal event
+        let mut joined = tip.clone();
+        joined.merge(&right);
+        let events = vec![root.time(), joined.time(), right.time(), tip.time()];
+        assert_eq!(frontier(&events), vec![1]);
+
+        assert!(frontier(&[]).is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge_within() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let unregistered = Identifier::new();
+
+        let mut members = Vector::new();
+        members.add(id1.clone()).add(id2.clone());
+
+        let mut vclock = VClock::with_vector(members.clone());
+        vclock.increment(&id1);
+
+        let mut peer = VClock::new();
+        peer.increment(&id1);
+        peer.increment(&id1);
+        peer.increment(&id2);
+        peer.increment(&unregistered);
+
+        vclock.merge_within(&peer, &members);
+        let current_time = vclock.time();
+
+        assert_eq!(*current_time.0.get(&id1).unwrap(), 2);
+        assert_eq!(*current_time.0.get(&id2).unwrap(), 1);
+        assert!(!current_time.0.contains_key(&unregistered));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_time_might_dominate() {
+        let ids: Vec<Identifier> = (0..20).map(|_| Identifier::new()).collect();
+
+        let mut older = VClock::new();
+        for id in ids.iter().step_by(3) {
+            older.increment(id);
+        }
+
+        let mut newer = older.clone();
+        for id in &ids {
+            newer.increment(id);
+        }
+
+        // A true domination is never ruled out by the fingerprint
+        assert!(newer.time() >= older.time());
+        assert!(newer.time().might_dominate(&older.time()));
+        assert!(newer.time().might_dominate(&newer.time()));
+
+        // Every snapshot might dominate the empty clock
+        assert!(older.time().might_dominate(&VClock::new().time()));
+
+        // Counters are part of the fingerprint, so a node that is far behind
+        // rules domination out even though both snapshots contain it
+        let node = Identifier::from_bytes(vec![7]);
+        let mut ahead = VClock::new();
+        for _ in 0..20 {
+            ahead.increment(&node);
+        }
+        let mut behind = VClock::new();
+        behind.increment(&node);
+        assert!(!behind.time().might_dominate(&ahead.time()));
+        assert!(ahead.time().might_dominate(&behind.time()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_time_might_dominate_has_no_false_negatives() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let ids = Identifier::generate(4);
+
+        for _ in 0..200 {
+            let a = random_vclock(&mut rng, &ids);
+            let mut b = random_vclock(&mut rng, &ids);
+            b.merge(&a);
+            for _ in 0..rng.gen_range(0..40) {
+                b.increment(&ids[0]);
+            }
+            assert!(b.time().might_dominate(&a.time()));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge_from_slice() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::from_bytes(vec![7, 7, 7]);
+
+        let mut peer = VClock::new();
+        peer.increment(&id1);
+        peer.increment(&id2);
+        peer.increment(&id2);
+        peer.increment(&id3);
+        let bytes = peer.time().to_canonical_bytes();
+
+        let mut vclock = VClock::new();
+        vclock.increment(&id1);
+        vclock.increment(&id1);
+        vclock.increment(&id1);
+
+        let mut expected = vclock.clone();
+        expected.merge(&peer);
+
+        vclock.merge_from_slice(&bytes).unwrap();
+        assert_eq!(vclock, expected);
+
+        // Malformed input is rejected
+        assert_eq!(
+            VClock::new().merge_from_slice(&bytes[..bytes.len() - 1]),
+            Err(ClockError::Truncated)
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            VClock::new().merge_from_slice(&trailing),
+            Err(ClockError::TrailingBytes)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_already_seen() {
+        let sender = Identifier::new();
+        let stranger = Identifier::new();
+
+        let mut vclock = VClock::new();
+        vclock.increment(&sender);
+        vclock.increment(&sender);
+        vclock.increment(&sender);
+
+        // Seen
+        assert!(vclock.already_seen(&sender, 1));
+        assert!(vclock.already_seen(&sender, 3));
+
+        // Exactly the next event
+        assert!(!vclock.already_seen(&sender, 4));
+
+        // Not yet seen
+        assert!(!vclock.already_seen(&sender, 10));
+        assert!(!vclock.already_seen(&stranger, 1));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_hashed_vclock_rolling_hash() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::new();
+
+        let mut hashed = HashedVClock::new();
+        assert_eq!(hashed.rolling_hash(), hashed.time().canonical_hash());
+
+        hashed.increment(&id1);
+        hashed.increment(&id1);
+        hashed.increment(&id2);
+        assert_eq!(hashed.rolling_hash(), hashed.time().canonical_hash());
+
+        let mut peer = VClock::new();
+        peer.increment(&id1);
+        peer.increment(&id3);
+        peer.increment(&id3);
+        hashed.merge(&peer);
+        assert_eq!(hashed.rolling_hash(), hashed.time().canonical_hash());
+
+        // The same logical state reached in a different order hashes equally
+        let mut other = VClock::new();
+        other.merge(&peer);
+        other.increment(&id2);
+        other.increment(&id1);
+        let mut vector = Vector::new();
+        vector.add(id2.clone());
+        other.merge(&VClock::with_vector(vector));
+        let other = HashedVClock::with_clock(other);
+        assert_eq!(other.rolling_hash(), hashed.rolling_hash());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_time_delta_encoding() {
+        let ids: Vec<Identifier> = (0..50).map(|_| Identifier::new()).collect();
+
+        let mut vclock = VClock::new();
+        for id in &ids {
+            vclock.increment(id);
+        }
+        let baseline = vclock.time();
+
+        vclock.increment(&ids[3]);
+        vclock.increment(&ids[17]);
+        let extra = Identifier::new();
+        vclock.increment(&extra);
+        let current = vclock.time();
+
+        let delta = current.delta_encode(&baseline);
+        let full = current.to_canonical_bytes();
+        assert!(delta.len() * 10 < full.len());
+        assert_eq!(VClockTime::delta_decode(&baseline, &delta).unwrap(), current);
+
+        // Removed entries are reconstructed too
+        let mut smaller = current.clone();
+        smaller.0.remove(&ids[0]);
+        let delta = smaller.delta_encode(&current);
+        assert_eq!(VClockTime::delta_decode(&current, &delta).unwrap(), smaller);
+
+        // Identical snapshots produce an empty delta
+        assert_eq!(current.delta_encode(&current), vec![0, 0, 0, 0]);
+
+        let mut corrupt = current.delta_encode(&baseline);
+        corrupt[4] = 9;
+        assert_eq!(
+            VClockTime::delta_decode(&baseline, &corrupt),
+            Err(ClockError::InvalidTag(9))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    fn random_vclock(rng: &mut impl rand::Rng, ids: &[Identifier]) -> VClock {
+        let mut vclock = VClock::new();
+        for id in ids {
+            // Leave some nodes absent and some explicitly at zero
+            match rng.gen_range(0..4) {
+                0 => {}
+                1 => vclock.merge(&VClock::with_vector({
+                    let mut vector = Vector::new();
+                    vector.add(id.clone());
+                    vector
+                })),
+                _ => {
+                    for _ in 0..rng.gen_range(1..5) {
+                        vclock.increment(id);
+                    }
+                }
+            }
+        }
+        vclock
+    }
+
+    #[cfg(feature = "std")]
+    fn merged(a: &VClock, b: &VClock) -> VClock {
+        let mut result = a.clone();
+        result.merge(b);
+        result
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge_lattice_laws() {
+        let mut rng = rand::thread_rng();
+        let ids: Vec<Identifier> = (0..4).map(|_| Identifier::new()).collect();
+
+        for _ in 0..500 {
+            let a = random_vclock(&mut rng, &ids);
+            let b = random_vclock(&mut rng, &ids);
+            let c = random_vclock(&mut rng, &ids);
+
+            // Commutative
+            assert_eq!(merged(&a, &b), merged(&b, &a));
+            // Associative
+            assert_eq!(merged(&merged(&a, &b), &c), merged(&a, &merged(&b, &c)));
+            // Idempotent
+            assert_eq!(merged(&a, &a), a);
+            // Equality agrees with the partial order
+            assert_eq!(
+                a.time() == b.time(),
+                a.time().partial_cmp(&b.time()) == Some(Ordering::Equal)
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_equality_ignores_zero_entries() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut vector = Vector::new();
+        vector.add(id2.clone());
+        let mut with_zero = VClock::with_vector(vector);
+        with_zero.increment(&id1);
+
+        let mut without_zero = VClock::new();
+        without_zero.increment(&id1);
+
+        assert_eq!(with_zero, without_zero);
+        assert_eq!(with_zero.time(), without_zero.time());
+        assert_eq!(with_zero.time().partial_cmp(&without_zero.time()), Some(Ordering::Equal));
+
+        // Merging with an empty clock is the identity
+        assert_eq!(merged(&with_zero, &VClock::new()), without_zero);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_event_stream() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::new();
+
+        let mut vector = Vector::new();
+        vector.add(id3.clone());
+        let mut vclock = VClock::with_vector(vector);
+        for _ in 0..3 {
+            vclock.increment(&id1);
+        }
+        vclock.increment(&id2);
+
+        let events: Vec<_> = vclock.event_stream().collect();
+        assert_eq!(events.len() as u64, vclock.total_events());
+        assert_eq!(vclock.total_events(), 4);
+
+        let id1_events: Vec<u64> = events
+            .iter()
+            .filter(|(node, _)| *node == id1)
+            .map(|(_, k)| *k)
+            .collect();
+        assert_eq!(id1_events, vec![1, 2, 3]);
+        assert!(events.contains(&(id2.clone(), 1)));
+        assert!(!events.iter().any(|(node, _)| *node == id3));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_transfer_estimate() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::new();
+
+        let mut vclock = VClock::new();
+        for _ in 0..5 {
+            vclock.increment(&id1);
+        }
+        vclock.increment(&id2);
+
+        let mut peer = VClock::new();
+        for _ in 0..2 {
+            peer.increment(&id1);
+        }
+        for _ in 0..4 {
+            peer.increment(&id2);
+        }
+        peer.increment(&id3);
+
+        // 3 events from id1; the peer is ahead on id2 and id3
+        assert_eq!(vclock.transfer_estimate(&peer.time()), 3);
+        assert_eq!(peer.transfer_estimate(&vclock.time()), 4);
+        assert_eq!(vclock.transfer_estimate(&vclock.time()), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_set_antichain() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut base = VClock::new();
+        base.increment(&id1);
+
+        let mut left = base.clone();
+        left.increment(&id1);
+        let mut right = base.clone();
+        right.increment(&id2);
+
+        let mut set = VClockSet::new();
+        assert!(set.insert(base.time()));
+
+        // Both branches dominate the base and are concurrent with each other
+        assert!(set.insert(left.time()));
+        assert!(set.insert(right.time()));
+        assert_eq!(set.len(), 2);
+        assert!(!set.members().contains(&base.time()));
+
+        // Stale and duplicate versions are skipped
+        assert!(!set.insert(base.time()));
+        assert!(!set.insert(left.time()));
+        assert_eq!(set.len(), 2);
+
+        // Merging the siblings replaces both
+        let mut joined = left.clone();
+        joined.merge(&right);
+        assert!(set.insert(joined.time()));
+        assert_eq!(set.members(), &[joined.time()]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_causal_sort() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut a = VClock::new();
+        a.increment(&id1);
+        let mut b = a.clone();
+        b.increment(&id1);
+        let mut c = a.clone();
+        c.increment(&id2);
+        let mut d = b.clone();
+        d.merge(&c);
+        d.increment(&id2);
+        let mut e = VClock::new();
+        e.increment(&id2);
+        e.increment(&id2);
+        e.increment(&id2);
+
+        let input = vec![
+            ("d", d.time()),
+            ("c", c.time()),
+            ("e", e.time()),
+            ("b", b.time()),
+            ("a", a.time()),
+        ];
+        let sorted = causal_sort(input.clone());
+        assert_eq!(sorted.len(), input.len());
+
+        // Every happens-before edge is respected
+        for (i, (_, earlier)) in sorted.iter().enumerate() {
+            for (_, later) in &sorted[..i] {
+                assert_ne!(earlier.partial_cmp(later), Some(Ordering::Less));
+            }
+        }
+
+        // Concurrent messages keep their input order
+        let names: Vec<&str> = sorted.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["e", "a", "c", "b", "d"]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_fold_clocks() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut a = VClock::new();
+        a.increment(&id1);
+        a.increment(&id1);
+        let mut b = VClock::new();
+        b.increment(&id1);
+        b.increment(&id2);
+        let mut c = VClock::new();
+        c.increment(&id2);
+        c.increment(&id2);
+        c.increment(&id2);
+
+        let stream = futures::stream::iter(vec![a.time(), b.time(), c.time()]);
+        let joined = futures::executor::block_on(fold_clocks(stream));
+
+        let mut expected = a.clone();
+        expected.merge(&b);
+        expected.merge(&c);
+        assert_eq!(joined, expected.time());
+
+        let empty = futures::executor::block_on(fold_clocks(futures::stream::iter(Vec::new())));
+        assert_eq!(empty, VClock::new().time());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge_with() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::new();
+
+        let mut vclock1 = VClock::new();
+        vclock1.increment(&id1);
+        vclock1.increment(&id1);
+        vclock1.increment(&id2);
+
+        let mut vclock2 = VClock::new();
+        vclock2.increment(&id1);
+        vclock2.increment(&id3);
+        vclock2.increment(&id3);
+
+        let mut calls = 0;
+        vclock1.merge_with(&vclock2, |_, a, b| {
+            calls += 1;
+            a + b
+        });
+        assert_eq!(calls, 3);
+
+        let current_time = vclock1.time();
+        assert_eq!(current_time.0[&id1], 3);
+        assert_eq!(current_time.0[&id2], 1);
+        assert_eq!(current_time.0[&id3], 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge_is_merge_with_max() {
+        let mut rng = rand::thread_rng();
+        let ids = Identifier::generate(4);
+
+        for _ in 0..200 {
+            let a = random_vclock(&mut rng, &ids);
+            let b = random_vclock(&mut rng, &ids);
+
+            let mut by_merge = a.clone();
+            by_merge.merge(&b);
+            let mut by_merge_with = a.clone();
+            by_merge_with.merge_with(&b, |_, x, y| x.max(y));
+            assert_eq!(by_merge, by_merge_with);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_time_fixed_bytes() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut vclock = VClock::new();
+        vclock.increment(&id1);
+        vclock.increment(&id2);
+        vclock.increment(&id2);
+        let time = vclock.time();
+
+        let bytes = time.to_bytes_fixed().unwrap();
+        assert_eq!(bytes.len(), 4 + 2 * 24);
+        assert_eq!(&bytes[..4], &[0, 0, 0, 2]);
+        assert_eq!(VClockTime::from_bytes_fixed(&bytes).unwrap(), time);
+
+        // Non-16-byte identifiers cannot use the fixed layout
+        vclock.increment(&Identifier::from_bytes(vec![1, 2, 3]));
+        assert_eq!(
+            vclock.time().to_bytes_fixed(),
+            Err(ClockError::InvalidIdentifierLength(3))
+        );
+
+        assert_eq!(
+            VClockTime::from_bytes_fixed(&bytes[..bytes.len() - 1]),
+            Err(ClockError::Truncated)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_audited_vclock_log() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut peer = VClock::new();
+        peer.increment(&id2);
+        peer.increment(&id2);
+
+        let mut audited = AuditedVClock::new();
+        audited.increment(&id1);
+        audited.merge(&peer);
+        audited.increment(&id1);
+
+        let mut expected = VClock::new();
+        let mut states = Vec::new();
+        expected.increment(&id1);
+        states.push(expected.time());
+        expected.merge(&peer);
+        states.push(expected.time());
+        expected.increment(&id1);
+        states.push(expected.time());
+
+        assert_eq!(
+            audited.audit_log(),
+            &[
+                (Operation::Increment(id1.clone()), states[0].clone()),
+                (Operation::Merge(peer.time()), states[1].clone()),
+                (Operation::Increment(id1.clone()), states[2].clone()),
+            ]
+        );
+        assert_eq!(audited.clock(), &expected);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_history_snapshot_at_or_before() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut vclock = VClock::new();
+        let mut history = VClockHistory::new();
+        assert_eq!(history.snapshot_at_or_before(&vclock.time()), None);
+
+        let mut snapshots = Vec::new();
+        for _ in 0..4 {
+            vclock.increment(&id1);
+            history.record(vclock.time());
+            snapshots.push(vclock.time());
+        }
+        assert_eq!(history.len(), 4);
+
+        // A reference from the middle of a linear history
+        assert_eq!(history.snapshot_at_or_before(&snapshots[1]), Some(&snapshots[1]));
+
+        // A reference that also saw another node still maps to the latest covered snapshot
+        let mut reference = VClock::new();
+        reference.increment(&id1);
+        reference.increment(&id1);
+        reference.increment(&id2);
+        assert_eq!(history.snapshot_at_or_before(&reference.time()), Some(&snapshots[1]));
+
+        // A reference concurrent with every snapshot has no answer
+        let mut concurrent = VClock::new();
+        concurrent.increment(&id2);
+        assert_eq!(history.snapshot_at_or_before(&concurrent.time()), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_record_local() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+
+        let mut vclock = VClock::new();
+        vclock.increment(&id2);
+        let before = vclock.time();
+
+        let stamp = vclock.record_local(&id1);
+        assert_eq!(stamp.0[&id1], 1);
+        assert_eq!(stamp.0[&id2], 1);
+        assert!(before < stamp);
+
+        let next = vclock.record_local(&id1);
+        assert_eq!(next.0[&id1], 2);
+        assert!(stamp < next);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge_base() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::new();
+
+        let mut common = VClock::new();
+        common.increment(&id1);
+        common.increment(&id1);
+        common.increment(&id2);
+
+        let mut ours = common.clone();
+        ours.increment(&id1);
+        ours.increment(&id3);
+
+        let mut theirs = common.clone();
+        theirs.increment(&id2);
+        theirs.increment(&id2);
+
+        let (base, ours_ahead, theirs_ahead) = ours.merge_base(&theirs);
+        assert_eq!(base, common);
+        assert_eq!(ours_ahead.time().0, HashMap::from([(id1.clone(), 1), (id3.clone(), 1)]));
+        assert_eq!(theirs_ahead.time().0, HashMap::from([(id2.clone(), 2)]));
+
+        // The parts recombine into the original clocks
+        let mut recombined = base.clone();
+        recombined.merge_with(&ours_ahead, |_, a, b| a + b);
+        assert_eq!(recombined, ours);
+
+        let mut recombined = base.clone();
+        recombined.merge_with(&theirs_ahead, |_, a, b| a + b);
+        assert_eq!(recombined, theirs);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge_up_to() {
+        let id1 = Identifier::new();
+        let id2 = Identifier::new();
+        let id3 = Identifier::new();
+
+        let mut cut = VClock::new();
+        for _ in 0..3 {
+            cut.increment(&id1);
+        }
+        cut.increment(&id2);
+
+        let mut peer = VClock::new();
+        for _ in 0..5 {
+            peer.increment(&id1);
+        }
+        peer.increment(&id3);
+
+        let mut vclock = VClock::new();
+        vclock.increment(&id2);
+        vclock.increment(&id2);
+
+        vclock.merge_up_to(&peer, &cut.time());
         let current_time = vclock.time();
 
-        assert_eq!(*current_time.0.get(&id1).unwrap(), 0);
-        assert_eq!(*current_time.0.get(&id2).unwrap(), 0);
+        // The peer's higher counter is clamped to the cut
+        assert_eq!(current_time.0[&id1], 3);
+        // Local progress beyond the cut is kept
+        assert_eq!(current_time.0[&id2], 2);
+        // Nodes outside the cut are not learned
+        assert!(!current_time.0.contains_key(&id3));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_increment_checked() {
+        let member = Identifier::new();
+        let stranger = Identifier::new();
+
+        let mut members = Vector::new();
+        members.add(member.clone());
+        let mut vclock = VClock::with_vector(members.clone());
+
+        assert_eq!(vclock.increment_checked(&member, &members), Ok(()));
+        assert_eq!(vclock.time().0[&member], 1);
+
+        assert_eq!(vclock.increment_checked(&stranger, &members), Err(ClockError::UnknownNode));
+        assert!(!vclock.time().0.contains_key(&stranger));
+    }
+
+    #[test]
+    fn test_vclock_dominant_node() {
+        let id1 = Identifier::from_bytes(vec![1]);
+        let id2 = Identifier::from_bytes(vec![2]);
+        let id3 = Identifier::from_bytes(vec![3]);
+
+        assert_eq!(VClock::new().dominant_node(), None);
+
+        let mut vclock = VClock::new();
+        vclock.increment(&id1);
+        vclock.increment(&id2);
+        vclock.increment(&id2);
+        vclock.increment(&id2);
+        vclock.increment(&id3);
+        assert_eq!(vclock.dominant_node(), Some((id2.clone(), 3)));
+
+        // Ties go to the smallest identifier
+        vclock.increment(&id3);
+        vclock.increment(&id3);
+        assert_eq!(vclock.dominant_node(), Some((id2.clone(), 3)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_dependencies() {
+        let ids = Identifier::generate(4);
+        let mut local = VClock::new();
+        local.increment(&ids[0]);
+        local.increment(&ids[1]);
+        local.increment(&ids[1]);
+
+        let mut sender = local.clone();
+        sender.increment(&ids[2]);
+        sender.increment(&ids[2]);
+
+        // ids[2] is the origin, ids[3] never appears
+        let mut expected = vec![(ids[0].clone(), 1), (ids[1].clone(), 2)];
+        expected.sort();
+        assert_eq!(local.dependencies(&sender.time()), expected);
+
+        // A missing dependency makes the origin ambiguous
+        sender.increment(&ids[3]);
+        assert_eq!(local.dependencies(&sender.time()).len(), 4);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_time_roster_encoding() {
+        let roster = Identifier::generate(10);
+        let mut vclock = VClock::new();
+        for (i, node) in roster.iter().enumerate().filter(|(i, _)| i % 3 != 0) {
+            for _ in 0..i {
+                vclock.increment(node);
+            }
+        }
+        let time = vclock.time();
+
+        let bytes = time.encode_roster(&roster).unwrap();
+        assert_eq!(bytes.len(), 2 + 6 * 8);
+        assert_eq!(VClockTime::decode_roster(&roster, &bytes).unwrap(), time);
+
+        // Far smaller than the formats that carry identifiers
+        assert!(bytes.len() < time.to_bytes_fixed().unwrap().len());
+        assert!(bytes.len() < time.to_canonical_bytes().len());
+        assert!(bytes.len() < time.to_bytes().unwrap().len());
+
+        assert_eq!(
+            time.encode_roster(&roster[..5]),
+            Err(ClockError::UnknownNode)
+        );
+        assert_eq!(
+            VClockTime::decode_roster(&roster, &bytes[..bytes.len() - 1]),
+            Err(ClockError::Truncated)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_detect_split_brain() {
+        let ids = Identifier::generate(3);
+        let mut primary = VClock::new();
+        primary.increment(&ids[0]);
+        primary.increment(&ids[0]);
+
+        // Converged: every replica reports a prefix of the same history
+        let mut behind = VClock::new();
+        behind.increment(&ids[0]);
+        let converged = HashMap::from([
+            (ids[0].clone(), primary.time()),
+            (ids[1].clone(), behind.time()),
+            (ids[2].clone(), primary.time()),
+        ]);
+        assert!(detect_split_brain(&converged).is_empty());
+
+        // Divergent: two replicas each accepted writes the other has not seen
+        let mut left = primary.clone();
+        left.increment(&ids[1]);
+        let mut right = primary.clone();
+        right.increment(&ids[2]);
+        let divergent = HashMap::from([
+            (ids[0].clone(), primary.time()),
+            (ids[1].clone(), left.time()),
+            (ids[2].clone(), right.time()),
+        ]);
+        let mut expected = (ids[1].clone(), ids[2].clone());
+        if expected.0 > expected.1 {
+            expected = (expected.1, expected.0);
+        }
+        assert_eq!(detect_split_brain(&divergent), vec![expected]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_within_tolerance() {
+        let ids = Identifier::generate(3);
+        let mut target = VClock::new();
+        let mut replica = VClock::new();
+        for _ in 0..5 {
+            target.increment(&ids[0]);
+            target.increment(&ids[1]);
+        }
+        for _ in 0..3 {
+            replica.increment(&ids[0]);
+        }
+        for _ in 0..6 {
+            replica.increment(&ids[1]);
+        }
+        // Ahead of the target on a node never counts against the replica
+        replica.increment(&ids[2]);
+
+        let target = target.time();
+        assert!(replica.within_tolerance(&target, 2));
+        assert!(replica.within_tolerance(&target, u64::MAX));
+        assert!(!replica.within_tolerance(&target, 1));
+        assert!(!replica.within_tolerance(&target, 0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_concurrent_with() {
+        let ids = Identifier::generate(2);
+        let mut base = VClock::new();
+        base.increment(&ids[0]);
+
+        let mut left = base.clone();
+        left.increment(&ids[0]);
+        let mut right = base.clone();
+        right.increment(&ids[1]);
+
+        // Truly concurrent, in both directions
+        assert!(left.concurrent_with(&right));
+        assert!(right.concurrent_with(&left));
+
+        // Equal, including a missing entry against an explicit zero
+        let mut zeroed = base.clone();
+        zeroed.vector.insert(ids[1].clone(), 0);
+        assert!(!base.concurrent_with(&zeroed));
+
+        // Strictly ordered
+        assert!(!base.concurrent_with(&left));
+        assert!(!left.concurrent_with(&base));
+        assert!(!VClock::new().concurrent_with(&right));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_causal_map_query_before() {
+        let ids = Identifier::generate(2);
+        let mut map = CausalMap::new();
+
+        let mut base = VClock::new();
+        base.increment(&ids[0]);
+        map.insert(base.time(), "base");
+
+        let mut left = base.clone();
+        left.increment(&ids[0]);
+        map.insert(left.time(), "left");
+
+        let mut right = base.clone();
+        right.increment(&ids[1]);
+        map.insert(right.time(), "right");
+
+        let mut joined = left.clone();
+        joined.merge(&right);
+        map.insert(joined.time(), "joined");
+
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.query_before(&left.time()), vec![&"base"]);
+        assert_eq!(map.query_before(&right.time()), vec![&"base"]);
+        assert_eq!(map.query_before(&joined.time()), vec![&"base", &"left", &"right"]);
+        assert!(map.query_before(&base.time()).is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_time_golden_bytes() {
+        let time = VClockTime(HashMap::from([(Identifier::from_bytes(vec![0xab, 0xcd]), 5)]));
+        let bytes = time.to_bytes().unwrap();
+        assert_eq!(
+            bytes,
+            vec![
+                1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0xab, 0xcd, 5, 0, 0, 0, 0, 0, 0, 0
+            ]
+        );
+        // The pinned configuration still matches bincode's legacy default
+        assert_eq!(bytes, bincode::serialize(&time.0).unwrap());
+        assert_eq!(VClockTime::from_bytes(&bytes).unwrap(), time);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_partial_ord_agrees_with_happened_before() {
+        let mut rng = rand::thread_rng();
+        let ids = Identifier::generate(3);
+
+        for _ in 0..500 {
+            let a = random_vclock(&mut rng, &ids);
+            let b = random_vclock(&mut rng, &ids);
+            assert_eq!(a.happened_before(&b), a.partial_cmp(&b) == Some(Ordering::Less));
+            assert_eq!(a.happened_before(&b), a < b);
+            assert_eq!(b.happened_before(&a), a > b);
+            assert_eq!(a.partial_cmp(&b), a.time().partial_cmp(&b.time()));
+            assert_eq!(a == b, a.partial_cmp(&b) == Some(Ordering::Equal));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_prune_zeros() {
+        let ids = Identifier::generate(4);
+        let mut vector = Vector::new();
+        for id in &ids {
+            vector.add(id.clone());
+        }
+        let mut seeded = VClock::with_vector(vector);
+        seeded.increment(&ids[0]);
+        let mut pruned = seeded.clone();
+        pruned.prune_zeros();
+
+        let mut never_seeded = VClock::new();
+        never_seeded.increment(&ids[0]);
+        assert_eq!(pruned.time().0, never_seeded.time().0);
+        assert_eq!(pruned, seeded);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let other = random_vclock(&mut rng, &ids);
+            assert_eq!(pruned.happened_before(&other), seeded.happened_before(&other));
+            assert_eq!(other.happened_before(&pruned), other.happened_before(&seeded));
+            assert_eq!(pruned.partial_cmp(&other), seeded.partial_cmp(&other));
+            assert_eq!(merged(&pruned, &other), merged(&seeded, &other));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_peak_concurrency_burst() {
+        let ids = Identifier::generate(4);
+        let mut events = Vec::new();
+
+        // A sequential prefix on a single node
+        let mut base = VClock::new();
+        for _ in 0..3 {
+            base.increment(&ids[0]);
+            events.push(base.time());
+        }
+
+        // A burst of four concurrent events forked from the prefix
+        let mut forks = Vec::new();
+        for id in &ids {
+            let mut fork = base.clone();
+            fork.increment(id);
+            events.push(fork.time());
+            forks.push(fork);
+        }
+
+        // Everything joins back and continues sequentially
+        let mut joined = base.clone();
+        for fork in &forks {
+            joined.merge(fork);
+        }
+        for _ in 0..3 {
+            joined.increment(&ids[1]);
+            events.push(joined.time());
+        }
+
+        assert_eq!(peak_concurrency(&events), 4);
+        assert_eq!(peak_concurrency(&events[..3]), 1);
+        assert_eq!(peak_concurrency(&[]), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_get_contains() {
+        let ids = Identifier::generate(3);
+        let mut vector = Vector::new();
+        vector.add(ids[1].clone());
+        let mut vclock = VClock::with_vector(vector);
+        vclock.increment(&ids[0]);
+        vclock.increment(&ids[0]);
+
+        assert_eq!(vclock.get(&ids[0]), 2);
+        assert!(vclock.contains(&ids[0]));
+
+        // A zero-valued entry and a missing one behave the same
+        assert_eq!(vclock.get(&ids[1]), 0);
+        assert_eq!(vclock.get(&ids[2]), 0);
+        assert!(!vclock.contains(&ids[1]));
+        assert!(!vclock.contains(&ids[2]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_conflict_counting_merges() {
+        let ids = Identifier::generate(2);
+        let mut vclock = ConflictCountingVClock::new();
+        vclock.increment(&ids[0]);
+
+        // A peer that is strictly ahead: sequential
+        let mut ahead = vclock.clock().clone();
+        ahead.increment(&ids[1]);
+        vclock.merge(&ahead);
+        // A peer that is behind, and one that is equal: sequential
+        vclock.merge(&VClock::new());
+        vclock.merge(&ahead);
+        assert_eq!(vclock.conflict_count(), 0);
+
+        // Both sides advance independently: conflict
+        let mut peer = vclock.clock().clone();
+        peer.increment(&ids[1]);
+        vclock.increment(&ids[0]);
+        vclock.merge(&peer);
+        assert_eq!(vclock.conflict_count(), 1);
+
+        // After the merge the peer is covered, so merging it again is not a conflict
+        vclock.merge(&peer);
+        assert_eq!(vclock.conflict_count(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_iter_delta() {
+        let ids = Identifier::generate(3);
+        let mut remote = VClock::new();
+        remote.increment(&ids[0]);
+        remote.increment(&ids[1]);
+
+        let mut local = remote.clone();
+        local.increment(&ids[1]);
+        local.increment(&ids[2]);
+
+        let mut advanced: Vec<_> = (&local)
+            .into_iter()
+            .filter(|(node, counter)| *counter > remote.get(node))
+            .map(|(node, counter)| (node.clone(), counter))
+            .collect();
+        advanced.sort();
+        let mut expected = vec![(ids[1].clone(), 2), (ids[2].clone(), 1)];
+        expected.sort();
+        assert_eq!(advanced, expected);
+
+        assert_eq!(local.iter().len(), 3);
+        let mut total = 0;
+        for (_, counter) in &local {
+            total += counter;
+        }
+        assert_eq!(total, local.total_events());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_merge_many_matches_merge() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let ids = Identifier::generate(5);
+
+        for _ in 0..200 {
+            let start = random_vclock(&mut rng, &ids);
+            let others: Vec<VClock> = (0..rng.gen_range(0..5))
+                .map(|_| random_vclock(&mut rng, &ids))
+                .collect();
+
+            let mut expected = start.clone();
+            for other in &others {
+                expected.merge(other);
+            }
+            let mut actual = start.clone();
+            actual.merge_many(&others);
+
+            assert_eq!(actual.vector, expected.vector);
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_vclock_time_json_round_trip() {
+        let ids = [Identifier::new(), Identifier::from_bytes(vec![1, 2, 3])];
+        let mut vclock = VClock::new();
+        vclock.increment(&ids[0]);
+        vclock.increment(&ids[1]);
+        vclock.increment(&ids[1]);
+
+        let json = vclock.time().to_json();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[ids[0].to_string()], 1);
+        assert_eq!(parsed["AQID"], 2);
+        assert_eq!(VClockTime::from_json(&json).unwrap(), vclock.time());
+
+        assert!(matches!(VClockTime::from_json("[]"), Err(ClockError::Serialization(_))));
+        assert_eq!(VClockTime::from_json(r#"{"!":1}"#), Err(ClockError::InvalidIdentifier));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_descends_and_dominates() {
+        let mut rng = rand::thread_rng();
+        let ids = Identifier::generate(3);
+
+        for _ in 0..500 {
+            let a = random_vclock(&mut rng, &ids);
+            let b = random_vclock(&mut rng, &ids);
+            assert_eq!(a.descends(&b), a >= b);
+            assert_eq!(a.dominates(&b), b.happened_before(&a));
+            if a.happened_before(&b) {
+                assert!(b.descends(&a));
+                assert!(!a.descends(&b));
+            }
+            assert!(a.descends(&a));
+            assert!(!a.dominates(&a));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_clear_node_matches_zero() {
+        let mut rng = rand::thread_rng();
+        let ids = Identifier::generate(3);
+
+        for _ in 0..200 {
+            let clock = random_vclock(&mut rng, &ids);
+            let other = random_vclock(&mut rng, &ids);
+
+            let mut cleared = clock.clone();
+            cleared.clear_node(&ids[0]);
+            let mut zeroed = clock.clone();
+            zeroed.vector.insert(ids[0].clone(), 0);
+
+            assert!(!cleared.vector.contains_key(&ids[0]));
+            assert_eq!(cleared, zeroed);
+            assert_eq!(cleared.partial_cmp(&other), zeroed.partial_cmp(&other));
+            assert_eq!(cleared.happened_before(&other), zeroed.happened_before(&other));
+            assert_eq!(other.happened_before(&cleared), other.happened_before(&zeroed));
+
+            let mut reset = clock.clone();
+            reset.reset();
+            assert!(reset.vector.is_empty());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_delta_matches_full_merge() {
+        let mut rng = rand::thread_rng();
+        let ids: Vec<Identifier> = (0..5).map(|_| Identifier::new()).collect();
+
+        for _ in 0..200 {
+            let known = random_vclock(&mut rng, &ids);
+            let mut local = known.clone();
+            local.merge(&random_vclock(&mut rng, &ids));
+            let mut remote = known.clone();
+            remote.merge(&random_vclock(&mut rng, &ids));
+
+            let delta = local.delta_since(&known.time());
+            assert!(delta.0.len() <= local.time().0.len());
+
+            let mut applied = remote.clone();
+            applied.apply_delta(&delta);
+            assert_eq!(applied, merged(&remote, &local));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_with_seeded_vector() {
+        let ids = Identifier::generate(3);
+        let mut vector = Vector::new();
+        vector
+            .add_with(ids[0].clone(), 5)
+            .add_with(ids[1].clone(), 12)
+            .add(ids[2].clone());
+
+        let mut vclock = VClock::with_vector(vector);
+        let time = vclock.time();
+        assert_eq!(time.0[&ids[0]], 5);
+        assert_eq!(time.0[&ids[1]], 12);
+        assert_eq!(time.0[&ids[2]], 0);
+
+        vclock.increment(&ids[1]);
+        assert_eq!(vclock.time().0[&ids[1]], 13);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_join() {
+        let mut rng = rand::thread_rng();
+        let ids = Identifier::generate(4);
+
+        for _ in 0..100 {
+            let a = random_vclock(&mut rng, &ids);
+            let b = random_vclock(&mut rng, &ids);
+            let (a_before, b_before) = (a.clone(), b.clone());
+
+            let joined = a.join(&b);
+            assert_eq!(joined, b.join(&a));
+            assert_eq!(joined, &a | &b);
+            assert_eq!(joined, merged(&a, &b));
+            assert!(joined.descends(&a));
+            assert!(joined.descends(&b));
+            assert_eq!(a, a_before);
+            assert_eq!(b, b_before);
+        }
+    }
+
+    #[test]
+    fn test_bounded_vclock_eviction() {
+        let ids: Vec<Identifier> = (0..4u8).map(|i| Identifier::from_bytes(vec![i])).collect();
+        let mut vclock = BoundedVClock::with_capacity(3);
+        for (i, id) in ids.iter().take(3).enumerate() {
+            for _ in 0..=i {
+                vclock.increment(id);
+            }
+        }
+        assert_eq!(vclock.pruned_watermark(), 0);
+
+        // A new node evicts the lowest counter, never the node being incremented
+        vclock.increment(&ids[3]);
+        assert_eq!(vclock.time().0.len(), 3);
+        assert!(!vclock.clock().contains(&ids[0]));
+        assert!(vclock.clock().contains(&ids[3]));
+        assert_eq!(vclock.pruned_watermark(), 1);
+
+        // Merging evicts down to the capacity and keeps the higher watermark
+        let mut other = BoundedVClock::with_capacity(3);
+        for _ in 0..5 {
+            other.increment(&ids[0]);
+        }
+        vclock.merge(&other);
+        assert_eq!(vclock.time().0.len(), 3);
+        assert_eq!(vclock.clock().get(&ids[0]), 5);
+        assert!(!vclock.clock().contains(&ids[3]));
+        assert_eq!(vclock.pruned_watermark(), 1);
+
+        let mut large = VClock::new();
+        for (i, id) in ids.iter().enumerate() {
+            for _ in 0..(i + 1) * 10 {
+                large.increment(id);
+            }
+        }
+        let wrapped = BoundedVClock::with_clock(large, 2);
+        assert_eq!(wrapped.time().0.len(), 2);
+        assert_eq!(wrapped.pruned_watermark(), 20);
+    }
+
+    #[test]
+    fn test_bounded_vclock_compare() {
+        let ids: Vec<Identifier> = (0..3u8).map(|i| Identifier::from_bytes(vec![i])).collect();
+        let mut a = BoundedVClock::with_capacity(2);
+        a.increment(&ids[0]);
+        let mut b = a.clone();
+        b.increment(&ids[1]);
+        assert_eq!(a.compare(&b), Some(Ordering::Less));
+        assert_eq!(b.compare(&a), Some(Ordering::Greater));
+
+        let mut c = a.clone();
+        c.increment(&ids[2]);
+        assert_eq!(b.compare(&c), None);
+
+        // Pruning ids[0] from b hides whether b has seen a's entry
+        b.increment(&ids[1]);
+        b.increment(&ids[2]);
+        assert!(!b.clock().contains(&ids[0]));
+        assert_eq!(a.compare(&b), None);
+        assert_eq!(b.compare(&a), None);
+
+        assert_eq!(b.compare(&b.clone()), Some(Ordering::Equal));
+
+        // An entry above the other clock's watermark is still ordered with certainty
+        let mut pruned = BoundedVClock::with_capacity(1);
+        for _ in 0..3 {
+            pruned.increment(&ids[0]);
+        }
+        pruned.increment(&ids[1]);
+        assert_eq!(pruned.pruned_watermark(), 3);
+
+        let mut ahead = BoundedVClock::with_capacity(2);
+        for _ in 0..5 {
+            ahead.increment(&ids[0]);
+        }
+        ahead.increment(&ids[1]);
+        assert_eq!(ahead.compare(&pruned), Some(Ordering::Greater));
+        assert_eq!(pruned.compare(&ahead), Some(Ordering::Less));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bounded_vclock_deserialize_enforces_capacity() {
+        let ids: Vec<Identifier> = (0..3u8).map(|i| Identifier::from_bytes(vec![i])).collect();
+        let mut vclock = BoundedVClock::with_capacity(3);
+        for (i, id) in ids.iter().enumerate() {
+            for _ in 0..=i {
+                vclock.increment(id);
+            }
+        }
+        let bytes = bincode::serialize(&vclock).unwrap();
+        assert_eq!(bincode::deserialize::<BoundedVClock>(&bytes).unwrap(), vclock);
+
+        // An overfull clock is evicted down to its capacity on decode
+        let overfull = bincode::serialize(&(vclock.clock(), 2usize, 0u64)).unwrap();
+        let decoded: BoundedVClock = bincode::deserialize(&overfull).unwrap();
+        assert_eq!(decoded.capacity(), 2);
+        assert_eq!(decoded.time().0.len(), 2);
+        assert!(!decoded.clock().contains(&ids[0]));
+        assert_eq!(decoded.pruned_watermark(), 1);
+
+        let zero = bincode::serialize(&(vclock.clock(), 0usize, 0u64)).unwrap();
+        assert!(bincode::deserialize::<BoundedVClock>(&zero).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_vclock_time_merge_matches_vclock_merge() {
+        let mut rng = rand::thread_rng();
+        let ids = Identifier::generate(4);
+
+        for _ in 0..100 {
+            let clocks: Vec<VClock> = (0..3).map(|_| random_vclock(&mut rng, &ids)).collect();
+            let persisted: Vec<Vec<u8>> = clocks.iter().map(|clock| clock.time().to_bytes().unwrap()).collect();
+
+            let mut recovered = VClockTime::from_bytes(&persisted[0]).unwrap();
+            for bytes in &persisted[1..] {
+                recovered.merge(&VClockTime::from_bytes(bytes).unwrap());
+            }
+
+            let mut expected = clocks[0].clone();
+            expected.merge_many(&clocks[1..]);
+            assert_eq!(recovered, expected.time());
+            assert_eq!(clocks[0].time().merged(&clocks[1].time()), merged(&clocks[0], &clocks[1]).time());
+        }
+    }
+
+    #[test]
+    fn test_vclock_time_stable_hash() {
+        use std::collections::HashSet;
+
+        let ids: Vec<Identifier> = (0..16u8).map(|i| Identifier::from_bytes(vec![i])).collect();
+        let entries: Vec<(Identifier, u64)> = ids.iter().cloned().zip(1..).collect();
+
+        // Maps built in different insertion orders iterate differently but hash equally
+        let forward = VClockTime(entries.iter().cloned().collect());
+        let reverse = VClockTime(entries.iter().rev().cloned().collect());
+        assert_eq!(forward.stable_hash(), reverse.stable_hash());
+
+        // Zero-valued entries compare equal to missing ones and hash the same
+        let mut padded = forward.clone();
+        padded.0.insert(Identifier::from_bytes(vec![99]), 0);
+        assert_eq!(padded, forward);
+        assert_eq!(padded.stable_hash(), forward.stable_hash());
+
+        let mut changed = forward.clone();
+        changed.0.insert(ids[0].clone(), 7);
+        assert_ne!(changed.stable_hash(), forward.stable_hash());
+
+        // The value is pinned so persisted hashes keep matching
+        let single = VClockTime([(Identifier::from_bytes(vec![1]), 1)].into_iter().collect());
+        assert_eq!(single.stable_hash(), 0xc8bf_8059_e342_69b6);
+
+        let unique: HashSet<VClockTime> = [forward, reverse, padded, changed].into_iter().collect();
+        assert_eq!(unique.len(), 2);
     }
 }